@@ -0,0 +1,309 @@
+use crate::app::manifest::{HIDDEN, SECRET_DATA};
+use crate::input::keymap::Action;
+use anyhow::{anyhow, bail, Context};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use similar::{ChangeTag, TextDiff};
+use std::path::Path;
+
+const LAST_APPLIED: &str = "kubectl.kubernetes.io/last-applied-configuration";
+/// What is shown instead of a hidden value, which differs from the desired one
+const HIDDEN_CHANGED: &str = "<hidden, changed>";
+
+/// State of the diff view, comparing the live object with its desired state.
+pub struct Diff {
+    live: Value,
+    desired: Value,
+    source: String,
+    prune: bool,
+    reveal: bool,
+    lines: Vec<(ChangeTag, String)>,
+    offset: usize,
+}
+
+impl Diff {
+    /// Create a new diff for the live object, against the provided file or the last applied
+    /// configuration.
+    pub fn new(live: Value, file: Option<&Path>) -> anyhow::Result<Self> {
+        let (desired, source) = match file {
+            Some(file) => (from_file(&live, file)?, file.to_string_lossy().to_string()),
+            None => (last_applied(&live)?, "last-applied".to_string()),
+        };
+
+        let mut result = Self {
+            live,
+            desired,
+            source,
+            prune: true,
+            reveal: false,
+            lines: vec![],
+            offset: 0,
+        };
+        result.update();
+        Ok(result)
+    }
+
+    pub fn lines(&self) -> &[(ChangeTag, String)] {
+        &self.lines
+    }
+
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    pub fn title(&self) -> String {
+        let mut title = format!("Diff (-{}, +live", self.source);
+        if self.prune {
+            title.push_str(", only fields present in desired state");
+        }
+        if self.is_sensitive() && !self.reveal {
+            title.push_str(", values hidden");
+        }
+        title.push(')');
+        title
+    }
+
+    pub fn on_action(&mut self, action: Action) {
+        match action {
+            Action::Down => self.scroll(1),
+            Action::Up => self.scroll(-1),
+            Action::PageDown => self.scroll(20),
+            Action::PageUp => self.scroll(-20),
+            Action::First => self.offset = 0,
+            Action::Last => self.offset = self.lines.len().saturating_sub(1),
+            Action::NextChange => self.next_change(),
+            Action::PrevChange => self.prev_change(),
+            Action::TogglePruning => {
+                self.prune = !self.prune;
+                self.update();
+            }
+            Action::RevealValues if self.is_sensitive() => {
+                self.reveal = !self.reveal;
+                self.update();
+            }
+            _ => {}
+        }
+    }
+
+    fn scroll(&mut self, amount: isize) {
+        let max = self.lines.len().saturating_sub(1);
+        self.offset = self.offset.saturating_add_signed(amount).min(max);
+    }
+
+    fn next_change(&mut self) {
+        if let Some(pos) = self
+            .lines
+            .iter()
+            .skip(self.offset + 1)
+            .position(|(tag, _)| *tag != ChangeTag::Equal)
+        {
+            self.offset += pos + 1;
+        }
+    }
+
+    fn prev_change(&mut self) {
+        if let Some(pos) = self.lines[..self.offset]
+            .iter()
+            .rposition(|(tag, _)| *tag != ChangeTag::Equal)
+        {
+            self.offset = pos;
+        }
+    }
+
+    /// Check if the object carries sensitive data
+    fn is_sensitive(&self) -> bool {
+        self.live.get("kind").and_then(Value::as_str) == Some("Secret")
+    }
+
+    fn update(&mut self) {
+        let mut desired = normalize(self.desired.clone());
+        let mut live = normalize(self.live.clone());
+        if self.prune {
+            live = prune(live, &desired);
+        }
+        if self.is_sensitive() && !self.reveal {
+            hide_values(&mut desired, &mut live);
+        }
+
+        let desired = serde_yaml::to_string(&desired).unwrap_or_default();
+        let live = serde_yaml::to_string(&live).unwrap_or_default();
+
+        self.lines = TextDiff::from_lines(&desired, &live)
+            .iter_all_changes()
+            .map(|change| (change.tag(), change.value().trim_end().to_string()))
+            .collect();
+        self.offset = self.offset.min(self.lines.len().saturating_sub(1));
+    }
+}
+
+/// Get the desired state from the last applied configuration annotation.
+fn last_applied(live: &Value) -> anyhow::Result<Value> {
+    let last_applied = live["metadata"]["annotations"][LAST_APPLIED]
+        .as_str()
+        .ok_or_else(|| anyhow!("Object has no last applied configuration"))?;
+
+    serde_json::from_str(last_applied).context("Failed to parse last applied configuration")
+}
+
+/// Get the desired state from a local file, finding the document matching the live object.
+fn from_file(live: &Value, file: &Path) -> anyhow::Result<Value> {
+    let content = std::fs::read_to_string(file)
+        .with_context(|| format!("Failed to read {}", file.display()))?;
+
+    let documents = serde_yaml::Deserializer::from_str(&content)
+        .map(Value::deserialize)
+        .collect::<Result<Vec<_>, _>>()
+        .with_context(|| format!("Failed to parse {}", file.display()))?;
+
+    let matches = |doc: &Value| {
+        doc["apiVersion"] == live["apiVersion"]
+            && doc["kind"] == live["kind"]
+            && doc["metadata"]["name"] == live["metadata"]["name"]
+    };
+
+    match documents.as_slice() {
+        [single] if !matches(single) => bail!(
+            "{} contains {}, expected {}",
+            file.display(),
+            describe(single),
+            describe(live)
+        ),
+        documents => documents
+            .iter()
+            .find(|doc| matches(doc))
+            .cloned()
+            .ok_or_else(|| anyhow!("No document for {} in {}", describe(live), file.display())),
+    }
+}
+
+/// Describe an object by its API version, kind and name.
+fn describe(value: &Value) -> String {
+    format!(
+        "{} {} {}",
+        value["apiVersion"].as_str().unwrap_or_default(),
+        value["kind"].as_str().unwrap_or_default(),
+        value["metadata"]["name"].as_str().unwrap_or_default()
+    )
+}
+
+/// Hide the values of a secret, only telling which of the live ones differ from the desired ones.
+fn hide_values(desired: &mut Value, live: &mut Value) {
+    for field in SECRET_DATA {
+        let desired_values = desired[field].as_object().cloned().unwrap_or_default();
+        if let Some(values) = live.get_mut(field).and_then(Value::as_object_mut) {
+            for (key, value) in values {
+                let hidden = match desired_values.get(key) == Some(value) {
+                    true => HIDDEN,
+                    false => HIDDEN_CHANGED,
+                };
+                *value = Value::String(hidden.into());
+            }
+        }
+        if let Some(values) = desired.get_mut(field).and_then(Value::as_object_mut) {
+            for value in values.values_mut() {
+                *value = Value::String(HIDDEN.into());
+            }
+        }
+    }
+}
+
+/// Remove fields which are managed by the server.
+fn normalize(mut value: Value) -> Value {
+    if let Some(obj) = value.as_object_mut() {
+        obj.remove("status");
+        if let Some(metadata) = obj.get_mut("metadata").and_then(Value::as_object_mut) {
+            for field in [
+                "managedFields",
+                "resourceVersion",
+                "uid",
+                "creationTimestamp",
+                "generation",
+                "selfLink",
+            ] {
+                metadata.remove(field);
+            }
+            if let Some(annotations) = metadata
+                .get_mut("annotations")
+                .and_then(Value::as_object_mut)
+            {
+                annotations.remove(LAST_APPLIED);
+                if annotations.is_empty() {
+                    metadata.remove("annotations");
+                }
+            }
+        }
+    }
+    value
+}
+
+/// Reduce the live state to the fields present in the desired state.
+fn prune(live: Value, desired: &Value) -> Value {
+    match (live, desired) {
+        (Value::Object(live), Value::Object(desired)) => Value::Object(
+            live.into_iter()
+                .filter_map(|(k, v)| desired.get(&k).map(|d| (k, prune(v, d))))
+                .collect::<Map<_, _>>(),
+        ),
+        (Value::Array(live), Value::Array(desired)) => Value::Array(
+            live.into_iter()
+                .enumerate()
+                .map(|(i, v)| match desired.get(i) {
+                    Some(d) => prune(v, d),
+                    None => v,
+                })
+                .collect(),
+        ),
+        (live, _) => live,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde_json::json;
+    use std::io::Write;
+
+    fn secret(password: &str) -> Value {
+        json!({
+            "apiVersion": "v1",
+            "kind": "Secret",
+            "metadata": { "name": "credentials" },
+            "data": { "password": password, "user": "YWRtaW4=" },
+        })
+    }
+
+    #[test]
+    fn single_document_must_match() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        writeln!(
+            file,
+            "apiVersion: v1\nkind: ConfigMap\nmetadata:\n  name: other"
+        )
+        .unwrap();
+
+        let err = Diff::new(secret("c2VjcmV0"), Some(file.path()))
+            .err()
+            .unwrap()
+            .to_string();
+        assert!(err.ends_with("contains v1 ConfigMap other, expected v1 Secret credentials"));
+    }
+
+    #[test]
+    fn secret_values_are_hidden() {
+        let mut live = secret("c2VjcmV0");
+        live["metadata"]["annotations"] = json!({
+            LAST_APPLIED: serde_json::to_string(&secret("b2xk")).unwrap(),
+        });
+
+        let mut diff = Diff::new(live, None).unwrap();
+        let text: Vec<_> = diff.lines().iter().map(|(_, line)| line.as_str()).collect();
+        assert!(!text.iter().any(|line| line.contains("c2VjcmV0")));
+        assert!(text.contains(&"  password: <hidden, changed>"));
+        assert!(text.contains(&"  user: <hidden>"));
+
+        diff.on_action(Action::RevealValues);
+        assert!(diff
+            .lines()
+            .contains(&(ChangeTag::Insert, "  password: c2VjcmV0".to_string())));
+    }
+}
@@ -0,0 +1,174 @@
+use crate::app::state::list::Object;
+use crate::client::Client;
+use anyhow::{anyhow, bail, Context};
+use kube::{
+    api::{DynamicObject, PostParams},
+    Api,
+};
+use serde_json::Value;
+use std::io::Write;
+use std::process::Command;
+
+/// An object being edited with an external editor.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Edit {
+    pub object: Object,
+    pub content: String,
+}
+
+/// A failed attempt to apply an edit.
+#[derive(Debug)]
+pub struct EditFailure {
+    pub edit: Edit,
+    pub error: String,
+}
+
+impl Edit {
+    pub fn new(mut object: Object) -> anyhow::Result<Self> {
+        // managed fields are not to be edited by the user
+        if let Some(metadata) = object
+            .manifest
+            .get_mut("metadata")
+            .and_then(Value::as_object_mut)
+        {
+            metadata.remove("managedFields");
+        }
+
+        let content = serde_yaml::to_string(&object.manifest)?;
+        Ok(Self { object, content })
+    }
+
+    /// Run the external editor on the content, blocking until the editor exits.
+    ///
+    /// Returns `true` if the content was changed.
+    pub fn run_editor(&mut self) -> anyhow::Result<bool> {
+        let mut file = tempfile::Builder::new()
+            .prefix("podium-")
+            .suffix(".yaml")
+            .tempfile()?;
+        file.write_all(self.content.as_bytes())?;
+        file.flush()?;
+
+        let editor = std::env::var("VISUAL")
+            .or_else(|_| std::env::var("EDITOR"))
+            .unwrap_or_else(|_| "vi".to_string());
+        let mut args = editor.split_whitespace();
+        let program = args.next().unwrap_or("vi");
+
+        let status = Command::new(program)
+            .args(args)
+            .arg(file.path())
+            .status()
+            .with_context(|| format!("Failed to launch editor: {editor}"))?;
+        if !status.success() {
+            bail!("Editor exited with: {status}");
+        }
+
+        let content = std::fs::read_to_string(file.path())?;
+        let changed = content != self.content;
+        self.content = content;
+
+        Ok(changed)
+    }
+
+    /// The object to replace, parsed from the edited content.
+    ///
+    /// The name and namespace can't be changed, and the `resourceVersion` is always the one of
+    /// the object before editing. So the replace fails if the object was modified in the
+    /// meantime, no matter what the user did to the content.
+    fn desired(&self) -> anyhow::Result<DynamicObject> {
+        let mut object: DynamicObject =
+            serde_yaml::from_str(&self.content).context("Failed to parse manifest")?;
+
+        let metadata = &self.object.manifest["metadata"];
+        let name = metadata["name"].as_str();
+        if object.metadata.name.as_deref() != name {
+            bail!(
+                "The name can't be changed, expected '{}'",
+                name.unwrap_or_default()
+            );
+        }
+        let namespace = metadata["namespace"].as_str();
+        if object.metadata.namespace.is_some() && object.metadata.namespace.as_deref() != namespace
+        {
+            bail!(
+                "The namespace can't be changed, expected '{}'",
+                namespace.unwrap_or_default()
+            );
+        }
+
+        object.metadata.namespace = namespace.map(ToString::to_string);
+        object.metadata.resource_version = metadata["resourceVersion"]
+            .as_str()
+            .map(ToString::to_string);
+        Ok(object)
+    }
+
+    /// Replace the object with the edited content.
+    ///
+    /// This fails if the object was modified in the meantime.
+    pub async fn apply(&self, client: &Client) -> anyhow::Result<()> {
+        let object = self.desired()?;
+        let name = object
+            .metadata
+            .name
+            .clone()
+            .ok_or_else(|| anyhow!("Missing name"))?;
+        let resource = &self.object.resource;
+
+        client
+            .run(|context| async move {
+                let api: Api<DynamicObject> = match &object.metadata.namespace {
+                    Some(namespace) => Api::namespaced_with(context.client, namespace, resource),
+                    None => Api::default_namespaced_with(context.client, resource),
+                };
+                api.replace(&name, &PostParams::default(), &object).await
+            })
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use k8s_openapi::api::core::v1::ConfigMap;
+    use kube::discovery::ApiResource;
+    use serde_json::json;
+
+    fn edit(content: &str) -> Edit {
+        let object = Object {
+            resource: ApiResource::erase::<ConfigMap>(&()),
+            manifest: json!({
+                "apiVersion": "v1",
+                "kind": "ConfigMap",
+                "metadata": { "name": "settings", "namespace": "default", "resourceVersion": "7" },
+            }),
+        };
+        Edit {
+            content: content.to_string(),
+            ..Edit::new(object).unwrap()
+        }
+    }
+
+    #[test]
+    fn keeps_the_original_version() {
+        let object = edit("apiVersion: v1\nkind: ConfigMap\nmetadata:\n  name: settings\n")
+            .desired()
+            .unwrap();
+        assert_eq!(object.metadata.namespace.as_deref(), Some("default"));
+        assert_eq!(object.metadata.resource_version.as_deref(), Some("7"));
+    }
+
+    #[test]
+    fn rejects_other_objects() {
+        let renamed = edit("apiVersion: v1\nkind: ConfigMap\nmetadata:\n  name: other\n");
+        assert!(renamed.desired().is_err());
+
+        let moved = edit(
+            "apiVersion: v1\nkind: ConfigMap\nmetadata:\n  name: settings\n  namespace: other\n",
+        );
+        assert!(moved.desired().is_err());
+    }
+}
@@ -0,0 +1,535 @@
+use crate::app::state::list::Object;
+use crate::client::{Client, RunError};
+use crate::input::{events::Redraw, key::Key, keymap::Action};
+use anyhow::{anyhow, bail};
+use futures::FutureExt;
+use kube::{
+    api::{DynamicObject, Patch, PatchParams},
+    discovery::ApiResource,
+    Api,
+};
+use serde_json::{json, Map, Value};
+use tokio::task::JoinHandle;
+
+/// Annotations which may contain the values of secrets
+const SENSITIVE_ANNOTATIONS: &[&str] = &["kubectl.kubernetes.io/last-applied-configuration"];
+
+/// The metadata map an entry belongs to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Section {
+    Labels,
+    Annotations,
+}
+
+impl Section {
+    pub fn field(&self) -> &'static str {
+        match self {
+            Self::Labels => "labels",
+            Self::Annotations => "annotations",
+        }
+    }
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            Self::Labels => "Labels",
+            Self::Annotations => "Annotations",
+        }
+    }
+}
+
+/// A label or annotation, with its pending change.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Entry {
+    pub section: Section,
+    pub key: String,
+    pub value: String,
+    /// The value before editing, `None` for added entries
+    pub original: Option<String>,
+    pub removed: bool,
+    /// The value must not be shown, like the last applied configuration of a secret
+    pub sensitive: bool,
+}
+
+impl Entry {
+    pub fn is_changed(&self) -> bool {
+        self.removed || self.original.as_ref() != Some(&self.value)
+    }
+}
+
+/// Text being entered.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Input {
+    /// A new entry, as `key=value`
+    Add(Section, String),
+    /// The new value of the selected entry
+    Change(String),
+}
+
+/// What to do after an action was handled.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Response {
+    Continue,
+    Close,
+    Apply,
+}
+
+/// The outcome of applying the changes.
+enum Applied {
+    Done,
+    Failed(String),
+    /// The object was modified in the meantime, and loaded again
+    Reloaded(Object),
+}
+
+/// State of the label and annotation editor.
+pub struct LabelEditor {
+    object: Object,
+    entries: Vec<Entry>,
+    selected: usize,
+    input: Option<Input>,
+    /// Why applying the changes failed
+    error: Option<String>,
+    /// Applying the changes in the background
+    applying: Option<JoinHandle<Applied>>,
+}
+
+impl LabelEditor {
+    pub fn new(object: Object) -> Self {
+        let secret = object.resource.kind == "Secret";
+        let mut entries = vec![];
+        for section in [Section::Labels, Section::Annotations] {
+            let map = object.manifest["metadata"][section.field()].as_object();
+            for (key, value) in map.into_iter().flatten() {
+                let value = value.as_str().unwrap_or_default().to_string();
+                entries.push(Entry {
+                    section,
+                    key: key.clone(),
+                    original: Some(value.clone()),
+                    value,
+                    removed: false,
+                    sensitive: secret
+                        && section == Section::Annotations
+                        && SENSITIVE_ANNOTATIONS.contains(&key.as_str()),
+                });
+            }
+        }
+
+        Self {
+            object,
+            entries,
+            selected: 0,
+            input: None,
+            error: None,
+            applying: None,
+        }
+    }
+
+    /// The name of the object.
+    pub fn name(&self) -> &str {
+        self.object.manifest["metadata"]["name"]
+            .as_str()
+            .unwrap_or_default()
+    }
+
+    pub fn entries(&self) -> &[Entry] {
+        &self.entries
+    }
+
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    pub fn input(&self) -> Option<&Input> {
+        self.input.as_ref()
+    }
+
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    pub fn is_applying(&self) -> bool {
+        self.applying.is_some()
+    }
+
+    pub fn has_changes(&self) -> bool {
+        self.entries.iter().any(Entry::is_changed)
+    }
+
+    /// Check if the user is currently entering text.
+    pub fn is_input(&self) -> bool {
+        self.input.is_some()
+    }
+
+    pub fn on_action(&mut self, action: Action) -> Response {
+        if self.is_applying() && action != Action::Close {
+            return Response::Continue;
+        }
+
+        match action {
+            Action::Close => return Response::Close,
+            Action::Apply => return Response::Apply,
+            Action::Down => {
+                self.selected = (self.selected + 1).min(self.entries.len().saturating_sub(1))
+            }
+            Action::Up => self.selected = self.selected.saturating_sub(1),
+            Action::AddLabel => self.input = Some(Input::Add(Section::Labels, String::new())),
+            Action::AddAnnotation => {
+                self.input = Some(Input::Add(Section::Annotations, String::new()))
+            }
+            Action::ChangeValue => {
+                if let Some(entry) = self.entries.get(self.selected) {
+                    if !entry.sensitive && !entry.removed {
+                        self.input = Some(Input::Change(entry.value.clone()));
+                    }
+                }
+            }
+            Action::RemoveEntry => self.toggle_removal(),
+            _ => {}
+        }
+        Response::Continue
+    }
+
+    /// Handle pasted text while entering text, only keeping its first line.
+    pub fn on_paste(&mut self, text: &str) {
+        if let Some(Input::Add(_, text_input) | Input::Change(text_input)) = &mut self.input {
+            let line = text.lines().next().unwrap_or_default();
+            text_input.extend(line.chars().filter(|c| !c.is_control()));
+        }
+    }
+
+    /// Handle a key while entering text.
+    pub fn on_input(&mut self, key: Key) {
+        let Some(input) = &mut self.input else {
+            return;
+        };
+        let (Input::Add(_, text) | Input::Change(text)) = input;
+
+        match key {
+            Key::Esc => self.input = None,
+            Key::Backspace => {
+                text.pop();
+            }
+            Key::Char(c) => text.push(c),
+            Key::Enter => {
+                let result = match self.input.take() {
+                    Some(Input::Add(section, text)) => self.add(section, &text),
+                    Some(Input::Change(value)) => {
+                        if let Some(entry) = self.entries.get_mut(self.selected) {
+                            entry.value = value;
+                        }
+                        Ok(())
+                    }
+                    None => Ok(()),
+                };
+                self.error = result.err().map(|err| err.to_string());
+            }
+            _ => {}
+        }
+    }
+
+    /// Add an entry entered as `key=value`, or change the value of an existing one.
+    fn add(&mut self, section: Section, text: &str) -> anyhow::Result<()> {
+        let (key, value) = text.split_once('=').unwrap_or((text, ""));
+        let key = key.trim();
+        if key.is_empty() || key.contains(char::is_whitespace) {
+            bail!("Invalid key '{key}', expected key=value");
+        }
+
+        match self
+            .entries
+            .iter()
+            .position(|e| e.section == section && e.key == key)
+        {
+            Some(idx) => {
+                let entry = &mut self.entries[idx];
+                if entry.sensitive {
+                    bail!("The value of {key} can't be changed");
+                }
+                entry.value = value.to_string();
+                entry.removed = false;
+                self.selected = idx;
+            }
+            None => {
+                // keep the entries grouped by their section
+                let idx = match section {
+                    Section::Labels => self
+                        .entries
+                        .iter()
+                        .position(|e| e.section == Section::Annotations)
+                        .unwrap_or(self.entries.len()),
+                    Section::Annotations => self.entries.len(),
+                };
+                self.entries.insert(
+                    idx,
+                    Entry {
+                        section,
+                        key: key.to_string(),
+                        value: value.to_string(),
+                        original: None,
+                        removed: false,
+                        sensitive: false,
+                    },
+                );
+                self.selected = idx;
+            }
+        }
+        Ok(())
+    }
+
+    /// Remove the selected entry, or keep it after all. Added entries are dropped right away.
+    fn toggle_removal(&mut self) {
+        let Some(entry) = self.entries.get_mut(self.selected) else {
+            return;
+        };
+        match entry.original {
+            Some(_) => entry.removed = !entry.removed,
+            None => {
+                self.entries.remove(self.selected);
+                self.selected = self.selected.min(self.entries.len().saturating_sub(1));
+            }
+        }
+    }
+
+    /// The JSON merge patch of the changes, if there are any.
+    ///
+    /// It contains the `resourceVersion` of the object, so it fails if the object was modified in
+    /// the meantime.
+    pub fn patch(&self) -> Option<Value> {
+        let mut metadata = Map::new();
+        for section in [Section::Labels, Section::Annotations] {
+            let changes: Map<String, Value> = self
+                .entries
+                .iter()
+                .filter(|e| e.section == section && e.is_changed())
+                .filter(|e| !(e.removed && e.original.is_none()))
+                .map(|e| match e.removed {
+                    true => (e.key.clone(), Value::Null),
+                    false => (e.key.clone(), Value::String(e.value.clone())),
+                })
+                .collect();
+            if !changes.is_empty() {
+                metadata.insert(section.field().to_string(), Value::Object(changes));
+            }
+        }
+        if metadata.is_empty() {
+            return None;
+        }
+
+        metadata.insert(
+            "resourceVersion".to_string(),
+            self.object.manifest["metadata"]["resourceVersion"].clone(),
+        );
+        Some(json!({ "metadata": metadata }))
+    }
+
+    /// Start applying the changes to the object, unless there are none.
+    ///
+    /// The outcome is picked up by [`Self::poll`], once the redraw is requested. Closing the
+    /// editor in the meantime doesn't stop applying the changes.
+    pub fn apply(&mut self, client: &Client, redraw: &Redraw) -> Response {
+        let Some(patch) = self.patch() else {
+            return Response::Close;
+        };
+
+        let client = client.clone();
+        let object = self.object.clone();
+        let redraw = redraw.clone();
+        self.error = None;
+        self.applying = Some(tokio::spawn(async move {
+            let applied = apply(&client, object, patch).await;
+            redraw.request();
+            applied
+        }));
+        Response::Continue
+    }
+
+    /// Pick up the outcome of applying the changes, once it is known.
+    pub fn poll(&mut self) -> Response {
+        if !self.applying.as_ref().is_some_and(JoinHandle::is_finished) {
+            return Response::Continue;
+        }
+
+        match self.applying.take().and_then(FutureExt::now_or_never) {
+            Some(Ok(Applied::Done)) => return Response::Close,
+            Some(Ok(Applied::Failed(err))) => self.error = Some(err),
+            Some(Ok(Applied::Reloaded(object))) => {
+                *self = Self::new(object);
+                self.error = Some("Changed on the server, reloaded it".to_string());
+            }
+            Some(Err(err)) => self.error = Some(err.to_string()),
+            None => {}
+        }
+        Response::Continue
+    }
+}
+
+/// Apply a patch, loading the object again if it was modified in the meantime.
+async fn apply(client: &Client, object: Object, patch: Value) -> Applied {
+    let name = object.manifest["metadata"]["name"]
+        .as_str()
+        .unwrap_or_default();
+    let result = match patch_object(client, &object, &patch).await {
+        Ok(()) => {
+            log::info!("Labels and annotations of {name} updated");
+            return Applied::Done;
+        }
+        // the patch is bound to the version of the object, retrying it would fail again
+        Err(err) if is_conflict(&err) => {
+            log::warn!("Failed to update {name}: {err:#}");
+            get_object(client, &object).await.map(Applied::Reloaded)
+        }
+        Err(err) => Err(err),
+    };
+
+    result.unwrap_or_else(|err| {
+        log::warn!("Failed to update {name}: {err:#}");
+        Applied::Failed(format!("{err:#}"))
+    })
+}
+
+fn is_conflict(err: &anyhow::Error) -> bool {
+    matches!(
+        err.downcast_ref::<RunError<kube::Error>>(),
+        Some(RunError::Operation(kube::Error::Api(err))) if err.code == 409
+    )
+}
+
+async fn patch_object(client: &Client, object: &Object, patch: &Value) -> anyhow::Result<()> {
+    let (name, namespace) = reference(object)?;
+    client
+        .run(|context| async move {
+            api(context.client, namespace, &object.resource)
+                .patch(name, &PatchParams::default(), &Patch::Merge(patch))
+                .await
+        })
+        .await?;
+    Ok(())
+}
+
+/// The current state of the object.
+async fn get_object(client: &Client, object: &Object) -> anyhow::Result<Object> {
+    let (name, namespace) = reference(object)?;
+    let current = client
+        .run(|context| async move {
+            api(context.client, namespace, &object.resource)
+                .get(name)
+                .await
+        })
+        .await?;
+
+    Ok(Object {
+        resource: object.resource.clone(),
+        manifest: serde_json::to_value(current)?,
+    })
+}
+
+/// The name and namespace of an object.
+fn reference(object: &Object) -> anyhow::Result<(&str, Option<&str>)> {
+    let metadata = &object.manifest["metadata"];
+    let name = metadata["name"]
+        .as_str()
+        .ok_or_else(|| anyhow!("Missing name"))?;
+    Ok((name, metadata["namespace"].as_str()))
+}
+
+fn api(
+    client: kube::Client,
+    namespace: Option<&str>,
+    resource: &ApiResource,
+) -> Api<DynamicObject> {
+    match namespace {
+        Some(namespace) => Api::namespaced_with(client, namespace, resource),
+        None => Api::default_namespaced_with(client, resource),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use k8s_openapi::api::core::v1::{Pod, Secret};
+
+    fn editor<K: kube::Resource<DynamicType = ()>>(manifest: Value) -> LabelEditor {
+        LabelEditor::new(Object {
+            resource: ApiResource::erase::<K>(&()),
+            manifest,
+        })
+    }
+
+    fn keys(editor: &mut LabelEditor, text: &str) {
+        for c in text.chars() {
+            editor.on_input(Key::Char(c));
+        }
+    }
+
+    #[test]
+    fn patch() {
+        let mut editor = editor::<Pod>(json!({
+            "metadata": {
+                "name": "backend",
+                "resourceVersion": "7",
+                "labels": { "app": "backend", "tier": "web" },
+                "annotations": { "note": "hello" },
+            },
+        }));
+        assert_eq!(editor.patch(), None);
+
+        // remove the first label, and change the second one
+        editor.on_action(Action::RemoveEntry);
+        editor.on_action(Action::Down);
+        editor.on_action(Action::ChangeValue);
+        editor.on_input(Key::Backspace);
+        editor.on_input(Key::Backspace);
+        editor.on_input(Key::Backspace);
+        keys(&mut editor, "api");
+        editor.on_input(Key::Enter);
+
+        // add an annotation, then drop it again
+        editor.on_action(Action::AddAnnotation);
+        keys(&mut editor, "owner=team");
+        editor.on_input(Key::Enter);
+        assert_eq!(editor.selected(), 3);
+        editor.on_action(Action::RemoveEntry);
+
+        // and add a label
+        editor.on_action(Action::AddLabel);
+        keys(&mut editor, "debug=true");
+        editor.on_input(Key::Enter);
+        assert_eq!(editor.entries()[2].key, "debug");
+
+        assert_eq!(
+            editor.patch(),
+            Some(json!({
+                "metadata": {
+                    "resourceVersion": "7",
+                    "labels": { "app": null, "tier": "api", "debug": "true" },
+                },
+            }))
+        );
+    }
+
+    #[test]
+    fn invalid_key() {
+        let mut editor = editor::<Pod>(json!({ "metadata": { "name": "backend" } }));
+        editor.on_action(Action::AddLabel);
+        keys(&mut editor, "=value");
+        editor.on_input(Key::Enter);
+        assert!(editor.error().is_some());
+        assert!(editor.entries().is_empty());
+    }
+
+    #[test]
+    fn sensitive_annotations() {
+        let mut editor = editor::<Secret>(json!({
+            "metadata": {
+                "name": "credentials",
+                "annotations": {
+                    "kubectl.kubernetes.io/last-applied-configuration": "{\"data\":{}}",
+                },
+            },
+        }));
+        assert!(editor.entries()[0].sensitive);
+
+        editor.on_action(Action::ChangeValue);
+        assert_eq!(editor.input(), None);
+    }
+}
@@ -0,0 +1,231 @@
+use crate::input::{key::Key, keymap::Action};
+use serde_json::Value;
+
+/// The fields of a secret holding its values
+pub const SECRET_DATA: [&str; 2] = ["data", "stringData"];
+/// What is shown instead of a hidden value
+pub const HIDDEN: &str = "<hidden>";
+
+/// The format to render a manifest in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    Yaml,
+    Json,
+}
+
+/// State of the manifest viewer.
+pub struct Manifest {
+    value: Value,
+    format: Format,
+    hide_managed_fields: bool,
+    hide_status: bool,
+    reveal: bool,
+    lines: Vec<String>,
+    offset: usize,
+    search: Search,
+}
+
+#[derive(Default)]
+pub enum Search {
+    /// No search active
+    #[default]
+    None,
+    /// User is entering a search term
+    Input(String),
+    /// Searching for a term
+    Active(String),
+}
+
+impl Manifest {
+    pub fn new(value: Value) -> Self {
+        let mut result = Self {
+            value,
+            format: Format::Yaml,
+            hide_managed_fields: true,
+            hide_status: false,
+            reveal: false,
+            lines: vec![],
+            offset: 0,
+            search: Default::default(),
+        };
+        result.update();
+        result
+    }
+
+    pub fn format(&self) -> Format {
+        self.format
+    }
+
+    pub fn lines(&self) -> &[String] {
+        &self.lines
+    }
+
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    pub fn search(&self) -> &Search {
+        &self.search
+    }
+
+    /// A short description of the active options
+    pub fn options(&self) -> String {
+        let mut options = vec![match self.format {
+            Format::Yaml => "YAML",
+            Format::Json => "JSON",
+        }];
+        if self.hide_managed_fields {
+            options.push("no managedFields");
+        }
+        if self.hide_status {
+            options.push("no status");
+        }
+        if self.is_sensitive() && !self.reveal {
+            options.push("values hidden");
+        }
+        options.join(", ")
+    }
+
+    /// Check if the user is currently entering text.
+    pub fn is_input(&self) -> bool {
+        matches!(self.search, Search::Input(_))
+    }
+
+    /// Handle a key while entering text.
+    pub fn on_input(&mut self, key: Key) {
+        if let Search::Input(term) = &mut self.search {
+            match key {
+                Key::Esc => self.search = Search::None,
+                Key::Enter => {
+                    self.search = Search::Active(term.clone());
+                    self.find_next(self.offset);
+                }
+                Key::Backspace => {
+                    term.pop();
+                }
+                Key::Char(c) => term.push(c),
+                _ => {}
+            }
+        }
+    }
+
+    /// Handle pasted text while entering text, only keeping its first line.
+    pub fn on_paste(&mut self, text: &str) {
+        if let Search::Input(term) = &mut self.search {
+            let line = text.lines().next().unwrap_or_default();
+            term.extend(line.chars().filter(|c| !c.is_control()));
+        }
+    }
+
+    pub fn on_action(&mut self, action: Action) {
+        match action {
+            Action::Down => self.scroll(1),
+            Action::Up => self.scroll(-1),
+            Action::PageDown => self.scroll(20),
+            Action::PageUp => self.scroll(-20),
+            Action::First => self.offset = 0,
+            Action::Last => self.offset = self.lines.len().saturating_sub(1),
+            Action::ToggleFormat => {
+                self.format = match self.format {
+                    Format::Yaml => Format::Json,
+                    Format::Json => Format::Yaml,
+                };
+                self.update();
+            }
+            Action::ToggleManagedFields => {
+                self.hide_managed_fields = !self.hide_managed_fields;
+                self.update();
+            }
+            Action::ToggleStatus => {
+                self.hide_status = !self.hide_status;
+                self.update();
+            }
+            Action::RevealValues if self.is_sensitive() => {
+                self.reveal = !self.reveal;
+                self.update();
+            }
+            Action::Search => self.search = Search::Input(String::new()),
+            Action::NextMatch => self.find_next(self.offset + 1),
+            Action::PrevMatch => self.find_prev(),
+            _ => {}
+        }
+    }
+
+    fn scroll(&mut self, amount: isize) {
+        let max = self.lines.len().saturating_sub(1);
+        self.offset = self.offset.saturating_add_signed(amount).min(max);
+    }
+
+    fn find_next(&mut self, from: usize) {
+        let Search::Active(term) = &self.search else {
+            return;
+        };
+        if let Some(pos) = self
+            .lines
+            .iter()
+            .enumerate()
+            .skip(from)
+            .find(|(_, line)| line.contains(term.as_str()))
+            .map(|(i, _)| i)
+        {
+            self.offset = pos;
+        }
+    }
+
+    fn find_prev(&mut self) {
+        let Search::Active(term) = &self.search else {
+            return;
+        };
+        if let Some(pos) = self.lines[..self.offset]
+            .iter()
+            .rposition(|line| line.contains(term.as_str()))
+        {
+            self.offset = pos;
+        }
+    }
+
+    /// Check if the manifest carries sensitive data
+    fn is_sensitive(&self) -> bool {
+        self.value.get("kind").and_then(Value::as_str) == Some("Secret")
+    }
+
+    /// re-render the text lines from the value
+    fn update(&mut self) {
+        let mut value = self.value.clone();
+        let hide_values = self.is_sensitive() && !self.reveal;
+
+        if let Some(obj) = value.as_object_mut() {
+            if hide_values {
+                for data in SECRET_DATA {
+                    for (_, v) in obj
+                        .get_mut(data)
+                        .and_then(Value::as_object_mut)
+                        .into_iter()
+                        .flatten()
+                    {
+                        *v = Value::String(HIDDEN.into());
+                    }
+                }
+            }
+            if self.hide_status {
+                obj.remove("status");
+            }
+            if self.hide_managed_fields {
+                if let Some(metadata) = obj.get_mut("metadata").and_then(|m| m.as_object_mut()) {
+                    metadata.remove("managedFields");
+                }
+            }
+        }
+
+        let text = match self.format {
+            Format::Yaml => serde_yaml::to_string(&value).map_err(|err| err.to_string()),
+            Format::Json => serde_json::to_string_pretty(&value).map_err(|err| err.to_string()),
+        };
+
+        self.lines = match text {
+            Ok(text) => text.lines().map(ToString::to_string).collect(),
+            Err(err) => vec![format!("Failed to render manifest: {err}")],
+        };
+        self.offset = self.offset.min(self.lines.len().saturating_sub(1));
+    }
+}
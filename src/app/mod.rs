@@ -1,9 +1,23 @@
-use crate::app::state::list::ListWatcher;
-use crate::app::state::AppState;
-use crate::client::Client;
+use crate::app::diff::Diff;
+use crate::app::edit::{Edit, EditFailure};
+use crate::app::labels::{LabelEditor, Response};
+use crate::app::manifest::Manifest;
+use crate::app::state::columns::ColumnConfig;
+use crate::app::state::{registry::Registry, tabs::Tabs, AppState, Target, View};
+use crate::client::{Client, ClusterInfo};
+use crate::config::Config;
 use crate::input::key::Key;
+use crate::input::keymap::{Action, Keymap, Resolved, Scope};
+use crate::input::mouse::{Mouse, MouseKind};
 use crate::Args;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
+pub mod diff;
+pub mod edit;
+pub mod labels;
+pub mod manifest;
 pub mod state;
 pub mod ui;
 
@@ -11,6 +25,8 @@ pub mod ui;
 pub enum AppReturn {
     Exit,
     Continue,
+    /// Suspend the UI and edit an object
+    Edit(Edit),
 }
 
 pub struct App {
@@ -18,53 +34,328 @@ pub struct App {
     client: Client,
     args: Args,
     global: Global,
+    keymap: Keymap,
+    columns: HashMap<View, ColumnConfig>,
+    registry: Registry,
+    tabs: Tabs,
+    /// Views which are currently not shown, with the time they were left
+    views: HashMap<View, (AppState, Instant)>,
+    idle_timeout: Option<Duration>,
+    /// Where the client is connected to, filled in once known
+    cluster: Arc<Mutex<ClusterInfo>>,
 }
 
 #[derive(Default)]
 pub struct Global {
     pub logs: bool,
+    /// Show the live watchers
+    pub debug: bool,
     pub help: bool,
+    pub manifest: Option<Manifest>,
+    pub diff: Option<Diff>,
+    pub edit_failure: Option<EditFailure>,
+    pub labels: Option<LabelEditor>,
 }
 
 impl App {
-    pub fn new(args: Args) -> Self {
+    pub fn new(args: Args, config: &Config) -> anyhow::Result<Self> {
         let client = Client::new(args.clone());
-        Self {
-            state: AppState::Pods(ListWatcher::new(client.clone())),
+        Self::with_client(args, config, client)
+    }
+
+    /// Create a new application, using a specific client.
+    pub fn with_client(args: Args, config: &Config, client: Client) -> anyhow::Result<Self> {
+        let keymap = Keymap::new(&config.keys)?;
+        let cluster = Arc::new(Mutex::new(ClusterInfo {
+            context: args.context.clone(),
+            namespace: args.namespace.clone(),
+            ..Default::default()
+        }));
+        let registry = Registry::new(client.clone());
+
+        {
+            let client = client.clone();
+            let cluster = cluster.clone();
+            let redraw = registry.redraw().clone();
+            tokio::spawn(async move {
+                let info = client.info().await;
+                *cluster.lock().unwrap() = info;
+                redraw.request();
+            });
+        }
+
+        let mut app = Self {
+            state: AppState::Initializing,
+            registry,
             client,
             args,
             global: Default::default(),
-        }
+            keymap,
+            columns: config.columns.clone(),
+            tabs: Tabs::new(&config.tabs),
+            views: Default::default(),
+            idle_timeout: config.idle_timeout,
+            cluster,
+        };
+        app.switch(app.args.view.unwrap_or(View::Pods));
+
+        Ok(app)
     }
 
     /// Handle a user action
     pub async fn do_action(&mut self, key: Key) -> AppReturn {
         log::debug!("Key: {key:?}");
 
-        match key {
-            Key::Ctrl('c') | Key::Char('q') => return AppReturn::Exit,
-            Key::Esc => {
+        // text input bypasses the key bindings
+        if let Some(manifest) = &mut self.global.manifest {
+            if manifest.is_input() {
+                manifest.on_input(key);
+                return AppReturn::Continue;
+            }
+        }
+
+        if let Some(editor) = &mut self.global.labels {
+            if editor.is_input() {
+                editor.on_input(key);
+                return AppReturn::Continue;
+            }
+        }
+
+        let action = match self.keymap.resolve(key, &self.scopes()) {
+            Resolved::Action(action) => action,
+            Resolved::Pending | Resolved::Unbound => return AppReturn::Continue,
+        };
+        log::debug!("Action: {action:?}");
+
+        if self.global.edit_failure.is_some() {
+            match action {
+                Action::Quit => return AppReturn::Exit,
+                Action::Edit => {
+                    if let Some(failure) = self.global.edit_failure.take() {
+                        return AppReturn::Edit(failure.edit);
+                    }
+                }
+                Action::Close => self.global.edit_failure = None,
+                _ => {}
+            }
+            return AppReturn::Continue;
+        }
+
+        if let Some(editor) = &mut self.global.labels {
+            if action == Action::Quit {
+                return AppReturn::Exit;
+            }
+            let response = match editor.on_action(action) {
+                Response::Apply => editor.apply(&self.client, self.registry.redraw()),
+                response => response,
+            };
+            if response == Response::Close {
+                self.global.labels = None;
+            }
+            return AppReturn::Continue;
+        }
+
+        if let Some(list) = self.state.list() {
+            if list.confirmation().is_some() {
+                match action {
+                    Action::Quit => return AppReturn::Exit,
+                    Action::Confirm => list.confirm(true),
+                    Action::Cancel => list.confirm(false),
+                    action => list.on_confirmation_action(action),
+                }
+                return AppReturn::Continue;
+            }
+            if list.report().is_some() {
+                match action {
+                    Action::Quit => return AppReturn::Exit,
+                    Action::Close => list.dismiss_report(),
+                    _ => {}
+                }
+                return AppReturn::Continue;
+            }
+        }
+
+        if let Some(manifest) = &mut self.global.manifest {
+            match action {
+                Action::Quit => return AppReturn::Exit,
+                Action::Close => self.global.manifest = None,
+                action => manifest.on_action(action),
+            }
+            return AppReturn::Continue;
+        }
+
+        if let Some(diff) = &mut self.global.diff {
+            match action {
+                Action::Quit => return AppReturn::Exit,
+                Action::Close => self.global.diff = None,
+                action => diff.on_action(action),
+            }
+            return AppReturn::Continue;
+        }
+
+        match action {
+            Action::Quit => return AppReturn::Exit,
+            Action::Back => {
                 if self.global.help {
                     self.global.help = false;
+                } else if self.state.list().is_some_and(|list| list.clear_marks()) {
+                    // marks are dropped before the filter
+                } else if self.state.clear_filter() {
+                    // a filtered list goes back to showing everything
                 } else {
                     return AppReturn::Exit;
                 }
             }
-            Key::Char('d') => {
-                self.state = AppState::Deployments(ListWatcher::new(self.client.clone()))
+            Action::ViewOverview => self.switch(View::Overview),
+            Action::ViewDeployments => self.switch(View::Deployments),
+            Action::ViewPods => self.switch(View::Pods),
+            Action::ViewEvents => self.switch(View::Events),
+            Action::ViewConfigMaps => self.switch(View::ConfigMaps),
+            Action::ViewSecrets => self.switch(View::Secrets),
+            Action::ToggleLogs => self.global.logs = !self.global.logs,
+            Action::ToggleDebug => self.global.debug = !self.global.debug,
+            Action::Help => self.global.help = !self.global.help,
+            Action::ShowManifest => match self.state.selected_object() {
+                Some(Ok(object)) => self.global.manifest = Some(Manifest::new(object.manifest)),
+                Some(Err(err)) => log::warn!("Failed to serialize manifest: {err}"),
+                None => {}
+            },
+            Action::ShowDiff => match self.state.selected_object() {
+                Some(Ok(object)) => {
+                    match Diff::new(object.manifest, self.args.diff_file.as_deref()) {
+                        Ok(diff) => self.global.diff = Some(diff),
+                        Err(err) => log::warn!("Failed to create diff: {err:#}"),
+                    }
+                }
+                Some(Err(err)) => log::warn!("Failed to serialize manifest: {err}"),
+                None => {}
+            },
+            Action::EditLabels => match self.state.selected_object() {
+                Some(Ok(object)) => self.global.labels = Some(LabelEditor::new(object)),
+                Some(Err(err)) => log::warn!("Failed to serialize manifest: {err}"),
+                None => {}
+            },
+            Action::Edit => match self.state.selected_object().map(|o| Edit::new(o?)) {
+                Some(Ok(edit)) => return AppReturn::Edit(edit),
+                Some(Err(err)) => log::warn!("Failed to prepare edit: {err}"),
+                None => {}
+            },
+            Action::Open => {
+                if let Some(target) = self.state.target() {
+                    self.open(target);
+                }
+            }
+            Action::PrevView => self.prev(),
+            Action::NextView => self.next(),
+            action if action.tab().is_some() => {
+                if let Some(view) = action.tab().and_then(|tab| self.tabs.get(tab)) {
+                    self.switch(view);
+                }
             }
-            Key::Char('p') => self.state = AppState::Pods(ListWatcher::new(self.client.clone())),
-            Key::Char('l') => self.global.logs = !self.global.logs,
-            Key::Char('h') | Key::Char('?') => self.global.help = !self.global.help,
-            Key::Left => self.prev(),
-            Key::Right => self.next(),
-            _ => {
-                self.state.on_key(key).await;
+            action => {
+                self.state.on_action(action).await;
             }
         }
         AppReturn::Continue
     }
 
+    /// Handle a click or scrolling
+    pub async fn on_mouse(&mut self, mouse: Mouse) {
+        let scroll = match mouse.kind {
+            MouseKind::ScrollUp => Some(Action::Up),
+            MouseKind::ScrollDown => Some(Action::Down),
+            MouseKind::Click => None,
+        };
+
+        let popup =
+            self.global.labels.is_some() || self.state.list().is_some_and(|list| list.has_popup());
+        if self.global.help || self.global.edit_failure.is_some() || popup {
+            // nothing to click or scroll
+        } else if let Some(manifest) = &mut self.global.manifest {
+            if let Some(action) = scroll {
+                manifest.on_action(action);
+            }
+        } else if let Some(diff) = &mut self.global.diff {
+            if let Some(action) = scroll {
+                diff.on_action(action);
+            }
+        } else if let Some(view) = self.tabs.at(mouse.column, mouse.row) {
+            if mouse.kind == MouseKind::Click {
+                self.switch(view);
+            }
+        } else {
+            self.state.on_mouse(mouse).await;
+        }
+    }
+
+    /// Pick up the outcome of work done in the background, before rendering
+    pub fn poll(&mut self) {
+        if let Some(editor) = &mut self.global.labels {
+            if editor.poll() == Response::Close {
+                self.global.labels = None;
+            }
+        }
+    }
+
+    /// Handle pasted text, which is only accepted by text input
+    pub fn on_paste(&mut self, text: &str) {
+        if let Some(manifest) = &mut self.global.manifest {
+            manifest.on_paste(text);
+        }
+        if let Some(editor) = &mut self.global.labels {
+            editor.on_paste(text);
+        }
+    }
+
+    /// The active key binding scopes, in order of precedence.
+    fn scopes(&self) -> Vec<Scope> {
+        if self.global.manifest.is_some() {
+            vec![Scope::Manifest, Scope::Viewer]
+        } else if self.global.diff.is_some() {
+            vec![Scope::Diff, Scope::Viewer]
+        } else if self.global.edit_failure.is_some() {
+            vec![Scope::EditFailure]
+        } else if self.global.labels.is_some() {
+            vec![Scope::Labels]
+        } else if self.global.help {
+            vec![Scope::Global]
+        } else if let Some(list) = self.state.list().filter(|list| list.has_popup()) {
+            match list.confirmation() {
+                Some(confirmation) if confirmation.delete.is_some() => {
+                    vec![Scope::DeleteOptions, Scope::Confirmation]
+                }
+                Some(_) => vec![Scope::Confirmation],
+                None => vec![Scope::Report],
+            }
+        } else {
+            self.state.scopes()
+        }
+    }
+
+    /// Apply the result of an edit
+    pub async fn apply_edit(&mut self, edit: Edit, result: anyhow::Result<bool>) {
+        let error = match result {
+            Ok(false) => {
+                log::info!("Edit cancelled, no changes made");
+                return;
+            }
+            Ok(true) => match edit.apply(&self.client).await {
+                Ok(()) => {
+                    log::info!("Changes applied");
+                    return;
+                }
+                Err(err) => err,
+            },
+            Err(err) => err,
+        };
+
+        log::warn!("Failed to apply edit: {error:#}");
+        self.global.edit_failure = Some(EditFailure {
+            edit,
+            error: format!("{error:#}"),
+        });
+    }
+
     pub fn state(&self) -> &AppState {
         &self.state
     }
@@ -73,19 +364,81 @@ impl App {
         &self.global
     }
 
+    pub fn args(&self) -> &Args {
+        &self.args
+    }
+
+    pub fn keymap(&self) -> &Keymap {
+        &self.keymap
+    }
+
+    pub fn registry(&self) -> &Registry {
+        &self.registry
+    }
+
+    pub fn tabs(&self) -> &Tabs {
+        &self.tabs
+    }
+
+    pub fn cluster(&self) -> ClusterInfo {
+        self.cluster.lock().unwrap().clone()
+    }
+
+    /// The first key and a short label of the actions worth a hint, specific ones first.
+    pub fn hints(&self) -> Vec<(String, &'static str)> {
+        let mut hints: Vec<(String, &'static str)> = vec![];
+        for scope in self.scopes() {
+            for (action, keys) in self.keymap.bindings(scope) {
+                if let (Some(hint), Some(key)) = (action.hint(), keys.first()) {
+                    if !hints.iter().any(|(_, h)| *h == hint) {
+                        hints.push((key.to_string(), hint));
+                    }
+                }
+            }
+        }
+        hints
+    }
+
+    /// Switch to a different view, resuming it if it was shown before
+    pub fn switch(&mut self, view: View) {
+        let previous = std::mem::take(&mut self.state);
+        if let Some(previous_view) = previous.view() {
+            self.views.insert(previous_view, (previous, Instant::now()));
+        }
+        self.evict();
+
+        self.state = match self.views.remove(&view) {
+            Some((state, _)) => state,
+            None => {
+                let columns = self.columns.get(&view).cloned().unwrap_or_default();
+                AppState::new(view, &self.registry, columns)
+            }
+        };
+    }
+
+    /// Drop views which weren't shown for a while, and the watchers no longer needed.
+    fn evict(&mut self) {
+        if let Some(timeout) = self.idle_timeout {
+            self.views.retain(|_, (_, left)| left.elapsed() < timeout);
+        }
+        self.registry.evict_unused();
+    }
+
+    /// Switch to the list behind a tile of the overview
+    pub fn open(&mut self, target: Target) {
+        self.switch(target.view());
+        self.state.set_filter(target);
+    }
+
     pub fn prev(&mut self) {
-        self.state = match &self.state {
-            AppState::Initializing => AppState::Initializing,
-            AppState::Pods(_) => AppState::Deployments(ListWatcher::new(self.client.clone())),
-            AppState::Deployments(_) => AppState::Pods(ListWatcher::new(self.client.clone())),
+        if let Some(view) = self.state.view() {
+            self.switch(self.tabs.prev(view));
         }
     }
 
     pub fn next(&mut self) {
-        self.state = match &self.state {
-            AppState::Initializing => AppState::Initializing,
-            AppState::Pods(_) => AppState::Deployments(ListWatcher::new(self.client.clone())),
-            AppState::Deployments(_) => AppState::Pods(ListWatcher::new(self.client.clone())),
+        if let Some(view) = self.state.view() {
+            self.switch(self.tabs.next(view));
         }
     }
 }
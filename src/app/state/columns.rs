@@ -0,0 +1,361 @@
+use anyhow::bail;
+use ratatui::{layout::*, style::*, text::*, widgets::*};
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::Value;
+use serde_json_path::JsonPath;
+use std::{cmp::Ordering, sync::Arc};
+
+/// The symbol in front of the selected row
+pub const HIGHLIGHT_SYMBOL: &str = ">> ";
+
+/// The content of a row, for all built-in columns.
+pub struct RowContent {
+    pub cells: Vec<String>,
+    pub style: Style,
+}
+
+impl RowContent {
+    pub fn new(cells: Vec<String>) -> Self {
+        Self {
+            cells,
+            style: Style::default(),
+        }
+    }
+
+    pub fn style(mut self, style: Style) -> Self {
+        self.style = style;
+        self
+    }
+}
+
+/// Column configuration of a view.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ColumnConfig {
+    /// Columns to show, in order. Defaults to all built-in columns, followed by all custom ones.
+    pub show: Option<Vec<String>>,
+    /// Additional columns, evaluated using a JSONPath expression
+    pub custom: Vec<CustomColumn>,
+}
+
+/// A column, showing the result of a JSONPath expression.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CustomColumn {
+    pub name: String,
+    #[serde(deserialize_with = "deserialize_path")]
+    pub path: JsonPath,
+}
+
+/// The order of a list by one of its columns, chosen by clicking the column header.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Sort {
+    /// The index of the configured column
+    pub column: usize,
+    pub descending: bool,
+}
+
+impl Sort {
+    /// The order after clicking a column header: ascending, descending, then back to the default.
+    pub fn toggle(current: Option<Sort>, column: usize) -> Option<Sort> {
+        match current {
+            Some(sort) if sort.column == column && !sort.descending => Some(Sort {
+                column,
+                descending: true,
+            }),
+            Some(sort) if sort.column == column => None,
+            _ => Some(Sort {
+                column,
+                descending: false,
+            }),
+        }
+    }
+}
+
+enum Column<'c> {
+    Builtin(usize),
+    Custom(&'c CustomColumn),
+}
+
+impl ColumnConfig {
+    /// Validate the configuration against the built-in columns of a view.
+    pub fn validate(&self, builtin: &[&str]) -> anyhow::Result<()> {
+        for name in self.show.iter().flatten() {
+            if self.find(builtin, name).is_none() {
+                bail!(
+                    "Unknown column '{name}', available columns are: {}",
+                    builtin
+                        .iter()
+                        .copied()
+                        .chain(self.custom.iter().map(|c| c.name.as_str()))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                );
+            }
+        }
+        Ok(())
+    }
+
+    fn find(&self, builtin: &[&str], name: &str) -> Option<Column<'_>> {
+        if let Some(custom) = self
+            .custom
+            .iter()
+            .find(|c| c.name.eq_ignore_ascii_case(name))
+        {
+            return Some(Column::Custom(custom));
+        }
+        builtin
+            .iter()
+            .position(|b| b.eq_ignore_ascii_case(name))
+            .map(Column::Builtin)
+    }
+
+    fn resolve(&self, builtin: &[&str]) -> Vec<Column<'_>> {
+        match &self.show {
+            Some(show) => show
+                .iter()
+                .filter_map(|name| self.find(builtin, name))
+                .collect(),
+            None => (0..builtin.len())
+                .map(Column::Builtin)
+                .chain(self.custom.iter().map(Column::Custom))
+                .collect(),
+        }
+    }
+
+    /// The header of the configured columns.
+    pub fn header(&self, builtin: &[&str]) -> Vec<String> {
+        self.resolve(builtin)
+            .iter()
+            .map(|c| match c {
+                Column::Builtin(idx) => builtin[*idx].to_string(),
+                Column::Custom(custom) => custom.name.clone(),
+            })
+            .collect()
+    }
+
+    /// Build the rows of items, containing only the configured columns.
+    pub fn rows<K, F>(&self, builtin: &[&str], items: &[Arc<K>], make_row: F) -> Vec<RowContent>
+    where
+        K: Serialize,
+        F: Fn(&K) -> RowContent,
+    {
+        let columns = self.resolve(builtin);
+        let has_custom = columns.iter().any(|c| matches!(c, Column::Custom(_)));
+
+        items
+            .iter()
+            .map(|item| {
+                let mut content = make_row(item);
+                let value = match has_custom {
+                    true => serde_json::to_value(item.as_ref()).unwrap_or_default(),
+                    false => Value::Null,
+                };
+
+                let cells = columns
+                    .iter()
+                    .map(|c| match c {
+                        Column::Builtin(idx) => content
+                            .cells
+                            .get_mut(*idx)
+                            .map(std::mem::take)
+                            .unwrap_or_default(),
+                        Column::Custom(custom) => custom.value(&value),
+                    })
+                    .collect();
+
+                RowContent::new(cells).style(content.style)
+            })
+            .collect()
+    }
+
+    /// Sort items by a column, keeping the current order of items with the same value.
+    ///
+    /// Values are compared naturally (`pod-2` before `pod-10`), ages by the creation time.
+    pub fn sort<K, F>(&self, builtin: &[&str], items: &mut Vec<Arc<K>>, make_row: F, sort: Sort)
+    where
+        K: kube::Resource + Serialize,
+        F: Fn(&K) -> RowContent,
+    {
+        let header = self.header(builtin);
+        match header.get(sort.column).map(String::as_str) {
+            None => return,
+            // the youngest objects first
+            Some("Age") => items.sort_by(|a, b| {
+                b.meta()
+                    .creation_timestamp
+                    .cmp(&a.meta().creation_timestamp)
+            }),
+            Some(_) => {
+                let rows = self.rows(builtin, items, make_row);
+                let mut sorted = items.drain(..).zip(rows).collect::<Vec<_>>();
+                sorted.sort_by(|(_, a), (_, b)| {
+                    let cell = |row: &RowContent| row.cells.get(sort.column).cloned();
+                    natural_cmp(&cell(a).unwrap_or_default(), &cell(b).unwrap_or_default())
+                });
+                items.extend(sorted.into_iter().map(|(item, _)| item));
+            }
+        }
+
+        if sort.descending {
+            items.reverse();
+        }
+    }
+
+    /// Build a table of items, sizing the columns by their content.
+    ///
+    /// Returns the widths of the columns too, to find the column at a position.
+    pub fn table<'a, K, F>(
+        &self,
+        title: String,
+        builtin: &[&str],
+        items: &[Arc<K>],
+        make_row: F,
+        sort: Option<Sort>,
+    ) -> (Table<'a>, Vec<u16>)
+    where
+        K: Serialize,
+        F: Fn(&K) -> RowContent,
+    {
+        let mut header = self.header(builtin);
+        let rows = self.rows(builtin, items, make_row);
+
+        if let Some(sort) = sort {
+            if let Some(h) = header.get_mut(sort.column) {
+                h.push_str(if sort.descending { " ▼" } else { " ▲" });
+            }
+        }
+
+        let widths: Vec<u16> = header
+            .iter()
+            .enumerate()
+            .map(|(i, h)| {
+                let width = rows
+                    .iter()
+                    .filter_map(|row| row.cells.get(i))
+                    .map(|cell| Span::raw(cell.as_str()).width())
+                    .chain([Span::raw(h.as_str()).width()])
+                    .max()
+                    .unwrap_or_default();
+                width.try_into().unwrap_or(u16::MAX)
+            })
+            .collect();
+        let constraints = widths
+            .iter()
+            .map(|w| Constraint::Length(*w))
+            .collect::<Vec<_>>();
+
+        let theme = crate::ui::theme::theme();
+        let header = Row::new(
+            header
+                .into_iter()
+                .map(|h| Cell::from(h).style(theme.header)),
+        )
+        .height(1);
+        let rows = rows
+            .into_iter()
+            .map(|row| Row::new(row.cells).style(row.style));
+
+        let table = Table::new(rows, constraints)
+            .header(header)
+            .block(Block::default().borders(Borders::ALL).title(title))
+            .highlight_style(theme.selected)
+            .highlight_symbol(HIGHLIGHT_SYMBOL);
+        (table, widths)
+    }
+}
+
+impl CustomColumn {
+    /// Evaluate the column for an object.
+    pub fn value(&self, value: &Value) -> String {
+        self.path
+            .query(value)
+            .all()
+            .into_iter()
+            .map(|v| match v {
+                Value::String(s) => s.clone(),
+                v => v.to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+}
+
+/// Compare strings, treating runs of digits as numbers.
+fn natural_cmp(a: &str, b: &str) -> Ordering {
+    let digits = |s: &str| s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    let (mut a, mut b) = (a, b);
+
+    loop {
+        let ordering = match (a.chars().next(), b.chars().next()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(x), Some(y)) if x.is_ascii_digit() && y.is_ascii_digit() => {
+                let (x, rest_a) = a.split_at(digits(a));
+                let (y, rest_b) = b.split_at(digits(b));
+                (a, b) = (rest_a, rest_b);
+
+                let (x, y) = (x.trim_start_matches('0'), y.trim_start_matches('0'));
+                x.len().cmp(&y.len()).then_with(|| x.cmp(y))
+            }
+            (Some(x), Some(y)) => {
+                (a, b) = (&a[x.len_utf8()..], &b[y.len_utf8()..]);
+                x.cmp(&y)
+            }
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+}
+
+/// Parse a JSONPath expression, also accepting the kubectl syntax like `{.spec.nodeName}`.
+fn deserialize_path<'de, D>(deserializer: D) -> Result<JsonPath, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let path = String::deserialize(deserializer)?;
+    let path = path.trim();
+    let path = path
+        .strip_prefix('{')
+        .and_then(|p| p.strip_suffix('}'))
+        .unwrap_or(path);
+    let path = match path.starts_with('.') {
+        true => format!("${path}"),
+        false => path.to_string(),
+    };
+
+    JsonPath::parse(&path).map_err(|err| serde::de::Error::custom(format!("{path}: {err}")))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_natural_cmp() {
+        assert_eq!(natural_cmp("pod-2", "pod-10"), Ordering::Less);
+        assert_eq!(natural_cmp("pod-10", "pod-9"), Ordering::Greater);
+        assert_eq!(natural_cmp("1/2", "1/2"), Ordering::Equal);
+        assert_eq!(natural_cmp("a", "b"), Ordering::Less);
+        assert_eq!(natural_cmp("007", "7"), Ordering::Equal);
+        assert_eq!(natural_cmp("pod", "pod-1"), Ordering::Less);
+    }
+
+    #[test]
+    fn test_toggle() {
+        let asc = Some(Sort {
+            column: 1,
+            descending: false,
+        });
+        let desc = Some(Sort {
+            column: 1,
+            descending: true,
+        });
+        assert_eq!(Sort::toggle(None, 1), asc);
+        assert_eq!(Sort::toggle(asc, 1), desc);
+        assert_eq!(Sort::toggle(desc, 1), None);
+        assert_eq!(Sort::toggle(desc, 0).map(|s| s.column), Some(0));
+    }
+}
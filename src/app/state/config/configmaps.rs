@@ -0,0 +1,71 @@
+use crate::app::state::config::{size, ConfigKind, ConfigResource};
+use crate::app::state::{
+    columns::RowContent,
+    list::{ListResource, Outcome},
+};
+use crate::client::Client;
+use crate::k8s::ago;
+use k8s_openapi::api::core::v1::ConfigMap;
+use kube::ResourceExt;
+use std::{convert::Infallible, future::Future, pin::Pin, sync::Arc};
+
+pub struct ConfigMaps;
+
+impl ListResource for ConfigMaps {
+    type Resource = ConfigMap;
+    type Message = Infallible;
+
+    const TITLE: &'static str = "ConfigMaps";
+    const COLUMNS: &'static [&'static str] = &["Name", "Keys", "Size", "Age"];
+
+    fn make_row(item: &Self::Resource) -> RowContent {
+        Self::make_row(item)
+    }
+
+    fn process(
+        _client: Arc<Client>,
+        msg: Self::Message,
+    ) -> Pin<Box<dyn Future<Output = Vec<Outcome>> + Send>> {
+        match msg {}
+    }
+}
+
+impl ConfigResource for ConfigMaps {
+    const KIND: ConfigKind = ConfigKind::ConfigMap;
+
+    fn entries(resource: &Self::Resource) -> Vec<(String, Vec<u8>)> {
+        let data = resource
+            .data
+            .iter()
+            .flatten()
+            .map(|(k, v)| (k.clone(), v.as_bytes().to_vec()));
+        let binary = resource
+            .binary_data
+            .iter()
+            .flatten()
+            .map(|(k, v)| (k.clone(), v.0.clone()));
+
+        let mut entries: Vec<_> = data.chain(binary).collect();
+        entries.sort_unstable_by(|a, b| a.0.cmp(&b.0));
+        entries
+    }
+}
+
+impl ConfigMaps {
+    fn make_row(cm: &ConfigMap) -> RowContent {
+        let entries = Self::entries(cm);
+
+        let age = cm
+            .creation_timestamp()
+            .as_ref()
+            .and_then(ago)
+            .unwrap_or_default();
+
+        RowContent::new(vec![
+            cm.name_any(),
+            entries.len().to_string(),
+            size(&entries),
+            age,
+        ])
+    }
+}
@@ -0,0 +1,135 @@
+use k8s_openapi::api::core::v1::{Pod, PodSpec};
+use std::fmt::{Display, Formatter};
+
+/// The kind of configuration resource a pod might reference.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConfigKind {
+    ConfigMap,
+    Secret,
+}
+
+/// How a pod references a configuration resource.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Usage {
+    Env,
+    EnvFrom,
+    Volume,
+    ImagePullSecret,
+}
+
+impl Display for Usage {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Env => f.write_str("env"),
+            Self::EnvFrom => f.write_str("envFrom"),
+            Self::Volume => f.write_str("volume"),
+            Self::ImagePullSecret => f.write_str("imagePullSecret"),
+        }
+    }
+}
+
+/// Find all the ways a pod references the named configuration resource.
+pub fn references(pod: &Pod, kind: ConfigKind, name: &str) -> Vec<Usage> {
+    let mut result = vec![];
+
+    if let Some(spec) = &pod.spec {
+        if references_env(spec, kind, name) {
+            result.push(Usage::Env);
+        }
+        if references_env_from(spec, kind, name) {
+            result.push(Usage::EnvFrom);
+        }
+        if references_volume(spec, kind, name) {
+            result.push(Usage::Volume);
+        }
+        if kind == ConfigKind::Secret
+            && spec
+                .image_pull_secrets
+                .iter()
+                .flatten()
+                .any(|s| s.name.as_deref() == Some(name))
+        {
+            result.push(Usage::ImagePullSecret);
+        }
+    }
+
+    result
+}
+
+fn containers(spec: &PodSpec) -> impl Iterator<Item = &k8s_openapi::api::core::v1::Container> {
+    spec.init_containers
+        .iter()
+        .flatten()
+        .chain(spec.containers.iter())
+}
+
+fn references_env(spec: &PodSpec, kind: ConfigKind, name: &str) -> bool {
+    containers(spec)
+        .flat_map(|c| c.env.iter().flatten())
+        .filter_map(|env| env.value_from.as_ref())
+        .any(|from| match kind {
+            ConfigKind::ConfigMap => from
+                .config_map_key_ref
+                .as_ref()
+                .is_some_and(|r| r.name.as_deref() == Some(name)),
+            ConfigKind::Secret => from
+                .secret_key_ref
+                .as_ref()
+                .is_some_and(|r| r.name.as_deref() == Some(name)),
+        })
+}
+
+fn references_env_from(spec: &PodSpec, kind: ConfigKind, name: &str) -> bool {
+    containers(spec)
+        .flat_map(|c| c.env_from.iter().flatten())
+        .any(|from| match kind {
+            ConfigKind::ConfigMap => from
+                .config_map_ref
+                .as_ref()
+                .is_some_and(|r| r.name.as_deref() == Some(name)),
+            ConfigKind::Secret => from
+                .secret_ref
+                .as_ref()
+                .is_some_and(|r| r.name.as_deref() == Some(name)),
+        })
+}
+
+fn references_volume(spec: &PodSpec, kind: ConfigKind, name: &str) -> bool {
+    spec.volumes.iter().flatten().any(|volume| {
+        let direct = match kind {
+            ConfigKind::ConfigMap => volume
+                .config_map
+                .as_ref()
+                .is_some_and(|c| c.name.as_deref() == Some(name)),
+            ConfigKind::Secret => volume
+                .secret
+                .as_ref()
+                .is_some_and(|s| s.secret_name.as_deref() == Some(name)),
+        };
+
+        let projected = volume
+            .projected
+            .iter()
+            .flat_map(|p| p.sources.iter().flatten())
+            .any(|source| match kind {
+                ConfigKind::ConfigMap => source
+                    .config_map
+                    .as_ref()
+                    .is_some_and(|c| c.name.as_deref() == Some(name)),
+                ConfigKind::Secret => source
+                    .secret
+                    .as_ref()
+                    .is_some_and(|s| s.name.as_deref() == Some(name)),
+            });
+
+        direct || projected
+    })
+}
+
+/// Render a value for display, falling back to a placeholder for binary content.
+pub fn display_value(value: &[u8]) -> String {
+    match std::str::from_utf8(value) {
+        Ok(value) => value.to_string(),
+        Err(_) => format!("<binary, {} bytes>", value.len()),
+    }
+}
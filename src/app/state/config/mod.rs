@@ -0,0 +1,154 @@
+mod configmaps;
+mod data;
+mod secrets;
+
+pub use configmaps::*;
+pub use data::*;
+pub use secrets::*;
+
+use crate::{
+    app::state::{
+        columns::ColumnConfig,
+        list::{ListResource, ListWatcher, Object},
+        registry::Registry,
+    },
+    input::{keymap::Action, mouse::Mouse},
+    ui::StateRenderer,
+};
+use k8s_openapi::api::core::v1::Pod;
+use k8s_openapi::serde::de::DeserializeOwned;
+use kube::ResourceExt;
+use ratatui::{layout::*, style::*, text::*, widgets::*};
+use std::{hash::Hash, sync::Arc};
+
+/// A resource holding configuration data, like a `ConfigMap` or `Secret`.
+pub trait ConfigResource: ListResource {
+    const KIND: ConfigKind;
+
+    /// All entries of the resource, key and raw value.
+    fn entries(resource: &Self::Resource) -> Vec<(String, Vec<u8>)>;
+}
+
+/// A list of configuration resources, with a detail pane for the selected one.
+pub struct ConfigView<R>
+where
+    R: ConfigResource,
+    <<R as ListResource>::Resource as kube::Resource>::DynamicType: Hash + Eq,
+{
+    list: ListWatcher<R>,
+    pods: ListWatcher<Pod>,
+    reveal: bool,
+}
+
+impl<R> ConfigView<R>
+where
+    R: ConfigResource + 'static,
+    <R::Resource as kube::Resource>::DynamicType: Hash + Eq + Clone + Default + DeserializeOwned,
+{
+    pub fn new(registry: &Registry, columns: ColumnConfig) -> Self {
+        Self {
+            list: ListWatcher::new(registry, columns),
+            pods: ListWatcher::new(registry, Default::default()),
+            reveal: false,
+        }
+    }
+
+    pub fn render<SR: StateRenderer>(&self, mut r: SR) {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+            .split(r.rect());
+
+        self.list.render(r.child(chunks[0]));
+
+        let detail = self.render_detail();
+        r.render_child(detail, chunks[1]);
+    }
+
+    fn render_detail<'a>(&self) -> Paragraph<'a> {
+        let block = Block::default().borders(Borders::ALL).title("Details");
+
+        let Some(selected) = self.list.selected() else {
+            return Paragraph::new("").block(block);
+        };
+
+        let bold = Style::default().add_modifier(Modifier::BOLD);
+        let mut lines = vec![Line::from(Span::styled("Data", bold))];
+
+        let entries = R::entries(&selected);
+        if entries.is_empty() {
+            lines.push(Line::from("  <none>"));
+        }
+        for (key, value) in entries {
+            let value = if R::KIND == ConfigKind::Secret && !self.reveal {
+                format!("<hidden, {} bytes>", value.len())
+            } else {
+                display_value(&value)
+            };
+            let mut value = value.lines();
+            lines.push(Line::from(vec![
+                Span::styled(format!("  {key}: "), bold),
+                Span::from(value.next().unwrap_or_default().to_string()),
+            ]));
+            lines.extend(value.map(|l| Line::from(format!("    {l}"))));
+        }
+
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled("Referenced by", bold)));
+
+        let name = selected.name_any();
+        let pods: Vec<_> = self
+            .pods
+            .items()
+            .into_iter()
+            .filter_map(|pod| {
+                let usages = references(&pod, R::KIND, &name);
+                (!usages.is_empty()).then(|| (pod.name_any(), usages))
+            })
+            .collect();
+
+        if pods.is_empty() {
+            lines.push(Line::from("  <none>"));
+        }
+        for (pod, usages) in pods {
+            let usages = usages
+                .iter()
+                .map(|u| u.to_string())
+                .collect::<Vec<_>>()
+                .join(", ");
+            lines.push(Line::from(format!("  {pod} ({usages})")));
+        }
+
+        Paragraph::new(lines)
+            .block(block)
+            .wrap(Wrap { trim: false })
+    }
+
+    pub fn items(&self) -> Vec<Arc<R::Resource>> {
+        self.list.items()
+    }
+
+    pub fn list(&self) -> &ListWatcher<R> {
+        &self.list
+    }
+
+    pub fn selected_object(&self) -> Option<serde_json::Result<Object>> {
+        self.list.selected_object()
+    }
+
+    pub async fn on_action(&mut self, action: Action) {
+        match action {
+            Action::RevealValues if R::KIND == ConfigKind::Secret => self.reveal = !self.reveal,
+            _ => self.list.on_action(action).await,
+        }
+    }
+
+    pub async fn on_mouse(&mut self, mouse: Mouse) {
+        self.list.on_mouse(mouse).await;
+    }
+}
+
+fn size(entries: &[(String, Vec<u8>)]) -> String {
+    let size: usize = entries.iter().map(|(_, v)| v.len()).sum();
+    size.to_string()
+}
@@ -0,0 +1,66 @@
+use crate::app::state::config::{size, ConfigKind, ConfigResource};
+use crate::app::state::{
+    columns::RowContent,
+    list::{ListResource, Outcome},
+};
+use crate::client::Client;
+use crate::k8s::ago;
+use k8s_openapi::api::core::v1::Secret;
+use kube::ResourceExt;
+use std::{convert::Infallible, future::Future, pin::Pin, sync::Arc};
+
+pub struct Secrets;
+
+impl ListResource for Secrets {
+    type Resource = Secret;
+    type Message = Infallible;
+
+    const TITLE: &'static str = "Secrets";
+    const COLUMNS: &'static [&'static str] = &["Name", "Type", "Keys", "Size", "Age"];
+
+    fn make_row(item: &Self::Resource) -> RowContent {
+        Self::make_row(item)
+    }
+
+    fn process(
+        _client: Arc<Client>,
+        msg: Self::Message,
+    ) -> Pin<Box<dyn Future<Output = Vec<Outcome>> + Send>> {
+        match msg {}
+    }
+}
+
+impl ConfigResource for Secrets {
+    const KIND: ConfigKind = ConfigKind::Secret;
+
+    fn entries(resource: &Self::Resource) -> Vec<(String, Vec<u8>)> {
+        // the API server returns the data base64 encoded, which is already decoded into bytes
+        // during deserialization
+        resource
+            .data
+            .iter()
+            .flatten()
+            .map(|(k, v)| (k.clone(), v.0.clone()))
+            .collect()
+    }
+}
+
+impl Secrets {
+    fn make_row(secret: &Secret) -> RowContent {
+        let entries = Self::entries(secret);
+
+        let age = secret
+            .creation_timestamp()
+            .as_ref()
+            .and_then(ago)
+            .unwrap_or_default();
+
+        RowContent::new(vec![
+            secret.name_any(),
+            secret.type_.clone().unwrap_or_default(),
+            entries.len().to_string(),
+            size(&entries),
+            age,
+        ])
+    }
+}
@@ -0,0 +1,316 @@
+use crate::client::{Client, RunError};
+use crate::input::keymap::Action;
+use k8s_openapi::{serde::de::DeserializeOwned, NamespaceResourceScope};
+use kube::{
+    api::{DeleteParams, PostParams, Preconditions, PropagationPolicy},
+    Api, Resource, ResourceExt,
+};
+use serde_json::json;
+use std::fmt::{self, Debug, Display, Formatter};
+
+/// How long objects get to shut down before they are removed.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum GracePeriod {
+    /// The grace period of the object
+    #[default]
+    Default,
+    Seconds(u32),
+    /// Remove the object right away, without waiting for its node (like `kubectl delete --force`)
+    Force,
+}
+
+impl GracePeriod {
+    const CHOICES: [Self; 5] = [
+        Self::Default,
+        Self::Seconds(1),
+        Self::Seconds(30),
+        Self::Seconds(120),
+        Self::Force,
+    ];
+
+    pub fn seconds(&self) -> Option<u32> {
+        match self {
+            Self::Default => None,
+            Self::Seconds(seconds) => Some(*seconds),
+            Self::Force => Some(0),
+        }
+    }
+
+    fn next(self) -> Self {
+        let idx = Self::CHOICES.iter().position(|c| *c == self).unwrap_or(0);
+        Self::CHOICES[(idx + 1) % Self::CHOICES.len()]
+    }
+}
+
+impl Display for GracePeriod {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Default => f.write_str("default"),
+            Self::Seconds(seconds) => write!(f, "{seconds}s"),
+            Self::Force => f.write_str("force (0s)"),
+        }
+    }
+}
+
+/// What happens to the dependents of deleted objects, like the pods of a replica set.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Propagation {
+    /// The default policy of the object
+    #[default]
+    Default,
+    Background,
+    Foreground,
+    Orphan,
+}
+
+impl Propagation {
+    const CHOICES: [Self; 4] = [
+        Self::Default,
+        Self::Background,
+        Self::Foreground,
+        Self::Orphan,
+    ];
+
+    pub fn policy(&self) -> Option<PropagationPolicy> {
+        match self {
+            Self::Default => None,
+            Self::Background => Some(PropagationPolicy::Background),
+            Self::Foreground => Some(PropagationPolicy::Foreground),
+            Self::Orphan => Some(PropagationPolicy::Orphan),
+        }
+    }
+
+    fn next(self) -> Self {
+        let idx = Self::CHOICES.iter().position(|c| *c == self).unwrap_or(0);
+        Self::CHOICES[(idx + 1) % Self::CHOICES.len()]
+    }
+}
+
+impl Display for Propagation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Default => "default",
+            Self::Background => "background",
+            Self::Foreground => "foreground",
+            Self::Orphan => "orphan",
+        })
+    }
+}
+
+/// How objects are deleted.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DeleteOptions {
+    pub grace_period: GracePeriod,
+    pub propagation: Propagation,
+    /// Evict instead of delete, which respects PodDisruptionBudgets
+    pub evict: bool,
+}
+
+/// Objects which are deleted along with others, unless they are orphaned.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Dependents {
+    pub count: usize,
+    /// The kind of the dependents, in plural
+    pub kind: &'static str,
+}
+
+impl DeleteOptions {
+    /// The parameters to delete an object, only if it wasn't changed or replaced since.
+    ///
+    /// Pods are only required not to be replaced, as their status changes all the time, like
+    /// when a crash looping container restarts.
+    pub fn params<K>(&self, object: &K) -> DeleteParams
+    where
+        K: Resource,
+        K::DynamicType: Default,
+    {
+        let resource_version = match K::kind(&Default::default()) == "Pod" {
+            true => None,
+            false => object.resource_version(),
+        };
+        DeleteParams {
+            grace_period_seconds: self.grace_period.seconds(),
+            propagation_policy: self.propagation.policy(),
+            preconditions: Some(Preconditions {
+                uid: object.uid(),
+                resource_version,
+            }),
+            ..Default::default()
+        }
+    }
+}
+
+/// The options of a deletion, chosen before confirming it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DeleteDialog {
+    pub options: DeleteOptions,
+    /// Whether the objects can be evicted, which only pods can
+    pub evictable: bool,
+    /// Whether all objects are terminating already
+    pub terminating: bool,
+    pub dependents: Option<Dependents>,
+}
+
+impl DeleteDialog {
+    /// Create the dialog, with the default options.
+    ///
+    /// Forcing the deletion is never the default, even for objects which are terminating already,
+    /// as it skips their graceful shutdown.
+    pub fn new(evictable: bool, terminating: bool) -> Self {
+        Self {
+            options: Default::default(),
+            evictable,
+            terminating,
+            dependents: None,
+        }
+    }
+
+    pub fn with_dependents(mut self, dependents: Option<Dependents>) -> Self {
+        self.dependents = dependents;
+        self
+    }
+
+    /// A warning about the dependents deleted too, if any.
+    pub fn warning(&self) -> Option<String> {
+        match self.dependents {
+            Some(Dependents { count, kind }) if count > 0 => match self.options.propagation {
+                Propagation::Orphan => None,
+                _ => Some(format!("This also deletes {count} {kind}")),
+            },
+            _ => None,
+        }
+    }
+
+    /// A hint that forcing the deletion is possible, for objects stuck in terminating.
+    pub fn hint(&self) -> Option<&'static str> {
+        match self.terminating && self.options.grace_period != GracePeriod::Force {
+            true => Some("Terminating already, a force (0s) grace period is available"),
+            false => None,
+        }
+    }
+
+    /// Change the option of an action.
+    pub fn on_action(&mut self, action: Action) {
+        let options = &mut self.options;
+        match action {
+            Action::ToggleEviction if self.evictable => options.evict = !options.evict,
+            Action::NextGracePeriod => options.grace_period = options.grace_period.next(),
+            Action::NextPropagation => options.propagation = options.propagation.next(),
+            _ => {}
+        }
+    }
+
+    /// The action changing it, name and current value of each option.
+    pub fn entries(&self) -> Vec<(Action, &'static str, String)> {
+        let mut entries = vec![];
+        if self.evictable {
+            let mode = match self.options.evict {
+                true => "evict",
+                false => "delete",
+            };
+            entries.push((Action::ToggleEviction, "Mode", mode.to_string()));
+        }
+        entries.push((
+            Action::NextGracePeriod,
+            "Grace period",
+            self.options.grace_period.to_string(),
+        ));
+        entries.push((
+            Action::NextPropagation,
+            "Propagation",
+            self.options.propagation.to_string(),
+        ));
+        entries
+    }
+}
+
+/// Delete or evict an object.
+pub async fn delete<K>(
+    client: &Client,
+    object: &K,
+    options: DeleteOptions,
+) -> Result<(), RunError<kube::Error>>
+where
+    K: Resource<Scope = NamespaceResourceScope> + Clone + Debug + DeserializeOwned,
+    K::DynamicType: Default,
+{
+    let name = object.name_any();
+    let namespace = object.namespace();
+    let params = options.params(object);
+
+    client
+        .run(|ctx| {
+            let api: Api<K> = match &namespace {
+                Some(namespace) => Api::namespaced(ctx.client, namespace),
+                None => ctx.api_namespaced(),
+            };
+            async move {
+                if options.evict {
+                    let eviction = json!({
+                        "apiVersion": "policy/v1",
+                        "kind": "Eviction",
+                        "metadata": { "name": name, "namespace": namespace },
+                        "deleteOptions": params,
+                    });
+                    let data = serde_json::to_vec(&eviction).map_err(kube::Error::SerdeError)?;
+                    api.create_subresource::<serde_json::Value>(
+                        "eviction",
+                        &name,
+                        &PostParams::default(),
+                        data,
+                    )
+                    .await?;
+                } else {
+                    api.delete(&name, &params).await?;
+                }
+                Ok(())
+            }
+        })
+        .await
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn dialog_options() {
+        let mut dialog = DeleteDialog::new(false, false);
+        assert_eq!(dialog.options, DeleteOptions::default());
+
+        // eviction is only offered when possible
+        dialog.on_action(Action::ToggleEviction);
+        assert!(!dialog.options.evict);
+        assert_eq!(dialog.entries().len(), 2);
+
+        for _ in 0..4 {
+            dialog.on_action(Action::NextGracePeriod);
+        }
+        assert_eq!(dialog.options.grace_period.seconds(), Some(0));
+        dialog.on_action(Action::NextGracePeriod);
+        assert_eq!(dialog.options.grace_period, GracePeriod::Default);
+
+        // terminating objects aren't forced by default, only hinted at
+        let mut dialog = DeleteDialog::new(true, true);
+        assert_eq!(dialog.options.grace_period, GracePeriod::Default);
+        assert!(dialog.hint().is_some());
+        assert_eq!(
+            dialog.entries()[0],
+            (Action::ToggleEviction, "Mode", "delete".to_string())
+        );
+        dialog.options.grace_period = GracePeriod::Force;
+        assert_eq!(dialog.hint(), None);
+
+        let dependents = Dependents {
+            count: 3,
+            kind: "pods",
+        };
+        let mut dialog = DeleteDialog::new(false, false).with_dependents(Some(dependents));
+        assert_eq!(
+            dialog.warning().as_deref(),
+            Some("This also deletes 3 pods")
+        );
+        dialog.options.propagation = Propagation::Orphan;
+        assert_eq!(dialog.warning(), None);
+    }
+}
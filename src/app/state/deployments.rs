@@ -1,19 +1,24 @@
-use crate::app::state::list::ListResource;
-use crate::client::Client;
-use crate::input::key::Key;
+use crate::app::state::{
+    columns::RowContent,
+    delete::Dependents,
+    list::{ListResource, Outcome},
+};
+use crate::client::{Client, RunError};
+use crate::input::keymap::Action;
 use crate::k8s::{ago, Scale};
+use crate::ui::theme::theme;
 use k8s_openapi::api::apps::v1::Deployment;
 use kube::{Api, Resource, ResourceExt};
-use ratatui::{layout::*, style::*, widgets::*};
+use ratatui::style::*;
 use std::future::Future;
 use std::hash::Hash;
 use std::pin::Pin;
 use std::sync::Arc;
 
 pub enum Msg {
-    Restart(Arc<Deployment>),
-    ScaleUp(Arc<Deployment>),
-    ScaleDown(Arc<Deployment>),
+    Restart(Vec<Arc<Deployment>>),
+    ScaleUp(Vec<Arc<Deployment>>),
+    ScaleDown(Vec<Arc<Deployment>>),
 }
 
 pub struct Deployments;
@@ -22,92 +27,82 @@ impl ListResource for Deployments {
     type Resource = Deployment;
     type Message = Msg;
 
-    fn render_table<'a>(items: &mut [Arc<Self::Resource>]) -> Table<'a>
-    where
-        <<Self as ListResource>::Resource as Resource>::DynamicType: Hash + Eq,
-    {
-        items.sort_unstable_by_key(|a| a.name_any());
+    const TITLE: &'static str = "Deployments";
+    const COLUMNS: &'static [&'static str] = &["Name", "Ready", "Updated", "Available", "Age"];
 
-        let selected_style = Style::default().add_modifier(Modifier::REVERSED);
-        let normal_style = Style::default();
-        let header_cells = ["Name", "Ready", "Updated", "Available", "Age"]
-            .iter()
-            .map(|h| Cell::from(*h).style(Style::default().add_modifier(Modifier::BOLD)));
-        let header = Row::new(header_cells).style(normal_style).height(1);
-
-        let rows: Vec<Row> = items
-            .iter()
-            .map(|deployment| Self::make_row(deployment))
-            .collect();
-
-        Table::new(
-            rows,
-            [
-                Constraint::Min(64),
-                Constraint::Min(15),
-                Constraint::Min(10),
-                Constraint::Min(10),
-            ],
-        )
-        .header(header)
-        .block(Block::default().borders(Borders::ALL).title("Deployments"))
-        .highlight_style(selected_style)
-        .highlight_symbol(">> ")
+    fn make_row(deployment: &Self::Resource) -> RowContent {
+        Self::make_row(deployment)
     }
 
-    fn on_key(items: &[Arc<Self::Resource>], state: &TableState, key: Key) -> Option<Self::Message>
+    fn on_action(targets: &[Arc<Self::Resource>], action: Action) -> Option<Self::Message>
     where
         <<Self as ListResource>::Resource as Resource>::DynamicType: Hash + Eq,
     {
-        match key {
-            Key::Char('r') => Self::with_selection(items, state, Msg::Restart),
-            Key::Char('+') => Self::with_selection(items, state, Msg::ScaleUp),
-            Key::Char('-') => Self::with_selection(items, state, Msg::ScaleDown),
+        if targets.is_empty() {
+            return None;
+        }
+        match action {
+            Action::Restart => Some(Msg::Restart(targets.to_vec())),
+            Action::ScaleUp => Some(Msg::ScaleUp(targets.to_vec())),
+            Action::ScaleDown => Some(Msg::ScaleDown(targets.to_vec())),
             _ => None,
         }
     }
 
+    /// The pods of the deployments, unless they are orphaned
+    fn dependents(targets: &[Arc<Self::Resource>]) -> Option<Dependents> {
+        let count = targets
+            .iter()
+            .filter_map(|d| d.status.as_ref().and_then(|s| s.replicas))
+            .map(|replicas| usize::try_from(replicas).unwrap_or_default())
+            .sum();
+        Some(Dependents {
+            count,
+            kind: "pods",
+        })
+    }
+
     fn process(
         client: Arc<Client>,
         msg: Self::Message,
-    ) -> Pin<Box<dyn Future<Output = ()> + Send>> {
-        Box::pin(async {
-            match msg {
-                Msg::Restart(deployment) => {
-                    Self::restart(client, &deployment).await;
-                }
-                Msg::ScaleUp(deployment) => {
-                    Self::scale(client, &deployment, 1).await;
-                }
-                Msg::ScaleDown(deployment) => {
-                    Self::scale(client, &deployment, -1).await;
-                }
+    ) -> Pin<Box<dyn Future<Output = Vec<Outcome>> + Send>> {
+        Box::pin(async move {
+            let (deployments, amount) = match msg {
+                Msg::Restart(deployments) => (deployments, None),
+                Msg::ScaleUp(deployments) => (deployments, Some(1)),
+                Msg::ScaleDown(deployments) => (deployments, Some(-1)),
+            };
+
+            let mut outcomes = vec![];
+            for deployment in deployments {
+                let result = match amount {
+                    None => Self::restart(client.clone(), &deployment).await,
+                    Some(amount) => Self::scale(client.clone(), &deployment, amount).await,
+                };
+                outcomes.push(Outcome::new(deployment.name_any(), result));
             }
+            outcomes
         })
     }
 }
 
 impl Deployments {
-    fn with_selection<F, I>(
-        deployments: &[Arc<Deployment>],
-        state: &TableState,
-        f: F,
-    ) -> Option<Msg>
-    where
-        F: FnOnce(Arc<Deployment>) -> I,
-        I: Into<Option<Msg>>,
-    {
-        let mut deployments = deployments.to_vec();
-        deployments.sort_unstable_by_key(|a| a.name_any());
-
-        if let Some(deployment) = state.selected().and_then(|i| deployments.get(i)) {
-            f(deployment.clone()).into()
-        } else {
-            None
-        }
+    /// Check if all desired replicas of a deployment are available
+    pub fn is_available(deployment: &Deployment) -> bool {
+        let desired = deployment
+            .spec
+            .as_ref()
+            .and_then(|spec| spec.replicas)
+            .unwrap_or(1);
+        let available = deployment
+            .status
+            .as_ref()
+            .and_then(|status| status.available_replicas)
+            .unwrap_or_default();
+        available >= desired
     }
 
-    fn make_row<'a>(deployment: &Deployment) -> Row<'a> {
+    fn make_row(deployment: &Deployment) -> RowContent {
         let mut style = Style::default();
 
         let name = deployment.name_any();
@@ -134,12 +129,12 @@ impl Deployments {
             .unwrap_or_default();
 
         if ready.0 == 0 {
-            style.fg = Some(Color::Red);
+            style = theme().deployment_unavailable;
         } else if ready.0 < ready.1 {
-            style.fg = Some(Color::Yellow);
+            style = theme().deployment_degraded;
         }
 
-        Row::new(vec![
+        RowContent::new(vec![
             name,
             format!("{}/{}", ready.0, ready.1),
             updated.to_string(),
@@ -149,17 +144,24 @@ impl Deployments {
         .style(style)
     }
 
-    async fn restart(client: Arc<Client>, deployment: &Deployment) {
-        let _ = client
+    async fn restart(
+        client: Arc<Client>,
+        deployment: &Deployment,
+    ) -> Result<(), RunError<kube::Error>> {
+        client
             .run(|ctx| {
                 let api: Api<Deployment> = ctx.api_namespaced();
-                async move { api.restart(&deployment.name_any()).await }
+                async move { api.restart(&deployment.name_any()).await.map(|_| ()) }
             })
-            .await;
+            .await
     }
 
-    async fn scale(client: Arc<Client>, deployment: &Deployment, amount: i32) {
-        let _ = client
+    async fn scale(
+        client: Arc<Client>,
+        deployment: &Deployment,
+        amount: i32,
+    ) -> Result<(), RunError<kube::Error>> {
+        client
             .run(|ctx| {
                 let api: Api<Deployment> = ctx.api_namespaced();
                 async move {
@@ -179,6 +181,6 @@ impl Deployments {
                     }
                 }
             })
-            .await;
+            .await
     }
 }
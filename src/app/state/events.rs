@@ -0,0 +1,84 @@
+use crate::app::state::{
+    columns::RowContent,
+    list::{ListResource, Outcome},
+};
+use crate::client::Client;
+use crate::k8s::ago;
+use crate::ui::theme::theme;
+use k8s_openapi::{api::core::v1::Event, apimachinery::pkg::apis::meta::v1::Time};
+use kube::ResourceExt;
+use ratatui::style::*;
+use std::{cmp::Reverse, convert::Infallible, future::Future, pin::Pin, sync::Arc};
+
+pub struct Events;
+
+impl ListResource for Events {
+    type Resource = Event;
+    type Message = Infallible;
+
+    const TITLE: &'static str = "Events";
+    const COLUMNS: &'static [&'static str] = &["Last Seen", "Type", "Reason", "Object", "Message"];
+
+    /// Sort by time, showing the latest events on top
+    fn sort(items: &mut [Arc<Self::Resource>]) {
+        items.sort_by_cached_key(|event| (Reverse(Self::last_seen(event)), event.name_any()));
+    }
+
+    fn make_row(event: &Self::Resource) -> RowContent {
+        Self::make_row(event)
+    }
+
+    fn process(
+        _client: Arc<Client>,
+        msg: Self::Message,
+    ) -> Pin<Box<dyn Future<Output = Vec<Outcome>> + Send>> {
+        match msg {}
+    }
+}
+
+impl Events {
+    /// The last time the event was observed
+    pub fn last_seen(event: &Event) -> Option<Time> {
+        event
+            .last_timestamp
+            .clone()
+            .or_else(|| event.event_time.as_ref().map(|t| Time(t.0)))
+            .or_else(|| event.first_timestamp.clone())
+            .or_else(|| event.creation_timestamp())
+    }
+
+    pub fn is_warning(event: &Event) -> bool {
+        event.type_.as_deref() == Some("Warning")
+    }
+
+    /// The object the event is about, like `pod/foo`
+    pub fn object(event: &Event) -> String {
+        let object = &event.involved_object;
+        format!(
+            "{}/{}",
+            object.kind.as_deref().unwrap_or_default().to_lowercase(),
+            object.name.as_deref().unwrap_or_default()
+        )
+    }
+
+    fn make_row(event: &Event) -> RowContent {
+        let style = match Self::is_warning(event) {
+            true => theme().pod_warning,
+            false => Style::default(),
+        };
+
+        let last_seen = Self::last_seen(event)
+            .as_ref()
+            .and_then(ago)
+            .unwrap_or_default();
+
+        RowContent::new(vec![
+            last_seen,
+            event.type_.clone().unwrap_or_default(),
+            event.reason.clone().unwrap_or_default(),
+            Self::object(event),
+            event.message.clone().unwrap_or_default(),
+        ])
+        .style(style)
+    }
+}
@@ -1,29 +1,55 @@
 use crate::{
+    app::state::{
+        columns::{ColumnConfig, RowContent, Sort, HIGHLIGHT_SYMBOL},
+        delete::{delete, DeleteDialog, DeleteOptions, Dependents},
+        registry::{Registry, State, Watcher},
+    },
     client::Client,
-    input::key::Key,
-    k8s::Reflector,
-    ui::{state::Paging, StateRenderer},
+    input::{
+        events::Redraw,
+        keymap::Action,
+        mouse::{Mouse, MouseKind},
+    },
+    ui::{state::Paging, theme::theme, StateRenderer},
 };
-use anyhow::anyhow;
-use futures::StreamExt;
-use k8s_openapi::serde::de::DeserializeOwned;
+use humantime::format_duration;
+use k8s_openapi::serde::{de::DeserializeOwned, Serialize};
 use k8s_openapi::NamespaceResourceScope;
 use kube::runtime::reflector::Store;
-use log::log_enabled;
-use ratatui::{style::*, text::*, widgets::*};
+use kube::{discovery::ApiResource, Resource, ResourceExt};
+use ratatui::{layout::*, style::*, text::*, widgets::*};
 use std::{
-    fmt::Debug,
+    collections::BTreeSet,
+    fmt::{Debug, Display},
     future::Future,
     hash::Hash,
     pin::Pin,
-    sync::{Arc, Mutex},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
 };
 use tokio::{
     spawn,
-    sync::mpsc::{channel, Receiver, Sender},
-    task::JoinHandle,
+    sync::{
+        mpsc::{channel, Receiver, Sender},
+        watch,
+    },
+    time::timeout,
 };
 
+/// The time an in-flight action gets to complete, when its list is dropped
+const CANCEL_GRACE_PERIOD: Duration = Duration::from_secs(5);
+
+/// The number of running action runners, to detect leaks
+static RUNNERS: AtomicUsize = AtomicUsize::new(0);
+
+/// The number of lists currently able to process actions.
+pub fn running_runners() -> usize {
+    RUNNERS.load(Ordering::Relaxed)
+}
+
 pub trait ListResource: Sized {
     type Resource: kube::Resource<Scope = NamespaceResourceScope>
         + Clone
@@ -32,72 +58,208 @@ pub trait ListResource: Sized {
         + Send
         + Sync
         + DeserializeOwned
+        + Serialize
         + 'static;
     type Message: Send + Sync + 'static;
 
+    /// The title of the list
+    const TITLE: &'static str;
+    /// The names of the built-in columns
+    const COLUMNS: &'static [&'static str];
+    /// Whether the objects can be evicted instead of deleted
+    const EVICTABLE: bool = false;
+
     fn render<SR: StateRenderer>(ctx: &Context<Self>, mut r: SR)
     where
         <<Self as ListResource>::Resource as kube::Resource>::DynamicType: Hash + Eq + Clone,
     {
-        let mut state = ctx.state.lock().unwrap();
+        let state = ctx.watcher.state.lock().unwrap();
 
         match *state {
             State::Loading => {
-                let table = Self::render_table(&mut []);
+                let (table, _) =
+                    Self::render_table(ctx.title(0), &[], &ctx.columns, None, &ctx.marked());
+                *ctx.layout.lock().unwrap() = None;
                 r.render(table);
             }
-            State::List(ref items, ref mut state) => {
-                let mut items = items.state();
-                let table = { Self::render_table(&mut items) };
+            State::List(ref items) => {
+                let items = ctx.visible(items);
+                let sort = *ctx.sort.lock().unwrap();
+                let marked = ctx.marked();
+                let count = items
+                    .iter()
+                    .filter(|item| marked.contains(&item.name_any()))
+                    .count();
+                let (table, widths) =
+                    Self::render_table(ctx.title(count), &items, &ctx.columns, sort, &marked);
+                let mut state = ctx.table.lock().unwrap();
                 let empty = items.is_empty();
 
-                if state.selected().is_none() && !empty {
-                    state.select(Some(0));
+                match state.selected() {
+                    None if !empty => state.select(Some(0)),
+                    // the selected item might have been removed
+                    Some(selected) if selected >= items.len() => {
+                        state.select(items.len().checked_sub(1))
+                    }
+                    _ => {}
                 }
 
-                r.render_stateful(table, state);
+                let mut layout = TableLayout {
+                    area: r.rect(),
+                    widths,
+                    highlight: state.selected().is_some(),
+                };
+
+                match ctx.watcher.stale() {
+                    Some((err, since)) => {
+                        // keep showing the last known content, explaining why it is outdated
+                        let chunks = Layout::default()
+                            .direction(Direction::Vertical)
+                            .constraints([Constraint::Min(3), Constraint::Length(1)])
+                            .split(r.rect());
+                        let status = Paragraph::new(format!(
+                            " Connection lost, last updated {} ago: {err}",
+                            format_duration(Duration::from_secs(since.as_secs()))
+                        ))
+                        .style(theme().error);
+
+                        layout.area = chunks[0];
+                        r.render_child_stateful(table, &mut state, chunks[0]);
+                        r.render_child(status, chunks[1]);
+                    }
+                    None => r.render_stateful(table, &mut state),
+                }
+                *ctx.layout.lock().unwrap() = Some(layout);
             }
             State::Error(ref err) => {
+                *ctx.layout.lock().unwrap() = None;
                 let err = err.to_string();
-                let w = Paragraph::new(err)
-                    .style(Style::default().bg(Color::Rgb(128, 0, 0)))
-                    .block(
-                        Block::default()
-                            .title(Span::styled(
-                                "Error",
-                                Style::default().add_modifier(Modifier::BOLD),
-                            ))
-                            .borders(Borders::ALL),
-                    );
+                let w = Paragraph::new(err).style(theme().error).block(
+                    Block::default()
+                        .title(Span::styled(
+                            "Error",
+                            Style::default().add_modifier(Modifier::BOLD),
+                        ))
+                        .borders(Borders::ALL),
+                );
                 r.render(w);
             }
         }
     }
 
-    fn render_table<'a>(items: &mut [Arc<Self::Resource>]) -> Table<'a>
+    /// Build the table of the items, returning the widths of its columns too.
+    fn render_table<'a>(
+        title: String,
+        items: &[Arc<Self::Resource>],
+        columns: &ColumnConfig,
+        sort: Option<Sort>,
+        marked: &BTreeSet<String>,
+    ) -> (Table<'a>, Vec<u16>)
     where
-        <<Self as ListResource>::Resource as kube::Resource>::DynamicType: Hash + Eq;
+        <<Self as ListResource>::Resource as kube::Resource>::DynamicType: Hash + Eq,
+    {
+        let make_row = |item: &Self::Resource| {
+            let row = Self::make_row(item);
+            match marked.contains(&item.name_any()) {
+                true => {
+                    let style = row.style.patch(theme().marked);
+                    row.style(style)
+                }
+                false => row,
+            }
+        };
+        columns.table(title, Self::COLUMNS, items, make_row, sort)
+    }
+
+    /// Sort items, in the order they are shown
+    fn sort(items: &mut [Arc<Self::Resource>]) {
+        items.sort_unstable_by_key(|a| a.name_any());
+    }
 
+    /// Create the content of the built-in columns for an item
+    fn make_row(item: &Self::Resource) -> RowContent;
+
+    /// Create the message for an action on the targets: the marked items, or else the selected one.
     #[allow(unused_variables)]
-    fn on_key(items: &[Arc<Self::Resource>], state: &TableState, key: Key) -> Option<Self::Message>
+    fn on_action(targets: &[Arc<Self::Resource>], action: Action) -> Option<Self::Message>
     where
         <<Self as ListResource>::Resource as kube::Resource>::DynamicType: Hash + Eq,
     {
         None
     }
 
-    fn process(client: Arc<Client>, msg: Self::Message)
-        -> Pin<Box<dyn Future<Output = ()> + Send>>;
+    /// Whether an action deletes the targets, which is confirmed in the delete dialog first.
+    fn deletes(action: Action) -> bool {
+        action == Action::Delete
+    }
+
+    /// The objects deleted along with the targets, like the pods of deployments.
+    #[allow(unused_variables)]
+    fn dependents(targets: &[Arc<Self::Resource>]) -> Option<Dependents> {
+        None
+    }
+
+    /// Process a message, returning the outcome for each object it affected.
+    fn process(
+        client: Arc<Client>,
+        msg: Self::Message,
+    ) -> Pin<Box<dyn Future<Output = Vec<Outcome>> + Send>>;
 }
 
-struct Runner<R>
-where
-    R: ListResource,
-    <<R as ListResource>::Resource as kube::Resource>::DynamicType: Hash + Eq,
-{
-    rx: Receiver<R::Message>,
+/// The result of an action on a single object.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Outcome {
+    pub name: String,
+    /// Why the action failed, if it did
+    pub error: Option<String>,
+}
+
+impl Outcome {
+    pub fn new<E: Display>(name: impl Into<String>, result: Result<(), E>) -> Self {
+        Self {
+            name: name.into(),
+            error: result.err().map(|err| format!("{err:#}")),
+        }
+    }
+}
+
+/// An action on several objects (or a deletion), waiting to be confirmed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Confirmation {
+    pub title: String,
+    /// The names of the affected objects
+    pub objects: Vec<String>,
+    /// The options of a deletion
+    pub delete: Option<DeleteDialog>,
+}
+
+/// The outcomes of an action on several objects.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Report {
+    pub title: String,
+    pub outcomes: Vec<Outcome>,
+}
+
+/// A task, along with what it does (like "Kill") to report its outcome.
+struct Job<R: ListResource> {
+    label: String,
+    task: Task<R>,
+}
+
+enum Task<R: ListResource> {
+    Message(R::Message),
+    Delete(Vec<Arc<R::Resource>>, DeleteOptions),
+}
+
+/// Processes the messages of a list, like actions on the selected item.
+struct Runner<R: ListResource> {
+    rx: Receiver<Job<R>>,
     client: Client,
-    ctx: Context<R>,
+    redraw: Redraw,
+    /// The outcomes of the last bulk action, until dismissed
+    report: Arc<Mutex<Option<Report>>>,
+    /// Closed when the list is dropped
+    cancel: watch::Receiver<()>,
 }
 
 pub struct ListWatcher<R>
@@ -105,18 +267,47 @@ where
     R: ListResource,
     <<R as ListResource>::Resource as kube::Resource>::DynamicType: Hash + Eq,
 {
-    _runner: JoinHandle<()>,
+    /// Cancels the runner when dropped
+    _cancel: watch::Sender<()>,
     ctx: Context<R>,
 }
 
-pub enum State<K>
-where
-    K: kube::Resource + 'static,
-    K::DynamicType: Hash + Eq,
-{
-    Loading,
-    List(Store<K>, TableState),
-    Error(anyhow::Error),
+/// An object, independent of its actual type.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Object {
+    pub resource: ApiResource,
+    pub manifest: serde_json::Value,
+}
+
+/// A named filter, limiting the items shown in a list.
+pub struct Filter<K> {
+    pub name: String,
+    matches: Arc<dyn Fn(&K) -> bool + Send + Sync>,
+}
+
+impl<K> Filter<K> {
+    pub fn new<F>(name: impl Into<String>, matches: F) -> Self
+    where
+        F: Fn(&K) -> bool + Send + Sync + 'static,
+    {
+        Self {
+            name: name.into(),
+            matches: Arc::new(matches),
+        }
+    }
+
+    pub fn matches(&self, item: &K) -> bool {
+        (self.matches)(item)
+    }
+}
+
+impl<K> Clone for Filter<K> {
+    fn clone(&self) -> Self {
+        Self {
+            name: self.name.clone(),
+            matches: self.matches.clone(),
+        }
+    }
 }
 
 pub struct Context<R>
@@ -124,21 +315,28 @@ where
     R: ListResource,
     <<R as ListResource>::Resource as kube::Resource>::DynamicType: Hash + Eq,
 {
-    pub state: Arc<Mutex<State<R::Resource>>>,
-    tx: Sender<R::Message>,
+    pub watcher: Arc<Watcher<R::Resource>>,
+    table: Mutex<TableState>,
+    tx: Sender<Job<R>>,
+    columns: ColumnConfig,
+    filter: Mutex<Option<Filter<R::Resource>>>,
+    /// The column chosen by clicking its header, instead of the default order
+    sort: Mutex<Option<Sort>>,
+    layout: Mutex<Option<TableLayout>>,
+    /// The names of the items marked for a bulk action
+    marked: Mutex<BTreeSet<String>>,
+    /// A bulk action, waiting to be confirmed
+    pending: Mutex<Option<(Confirmation, Job<R>)>>,
+    report: Arc<Mutex<Option<Report>>>,
 }
 
-impl<R> Clone for Context<R>
-where
-    R: ListResource,
-    <<R as ListResource>::Resource as kube::Resource>::DynamicType: Hash + Eq,
-{
-    fn clone(&self) -> Self {
-        Self {
-            state: self.state.clone(),
-            tx: self.tx.clone(),
-        }
-    }
+/// Where the table was rendered last, to find the row or column at a position.
+#[derive(Clone, Debug)]
+struct TableLayout {
+    area: Rect,
+    widths: Vec<u16>,
+    /// Whether space is reserved for the highlight symbol
+    highlight: bool,
 }
 
 impl<R> ListWatcher<R>
@@ -147,26 +345,40 @@ where
     <<R as ListResource>::Resource as kube::Resource>::DynamicType:
         Hash + Eq + Clone + Default + DeserializeOwned,
 {
-    pub fn new(client: Client) -> Self {
-        let (tx, rx) = channel::<R::Message>(10);
+    pub fn new(registry: &Registry, columns: ColumnConfig) -> Self {
+        let (tx, rx) = channel::<Job<R>>(10);
+        let report = Arc::new(Mutex::new(None));
 
         let ctx = Context {
+            watcher: registry.watch(),
+            table: Default::default(),
             tx,
-            state: Arc::new(Mutex::new(State::Loading)),
+            columns,
+            filter: Default::default(),
+            sort: Default::default(),
+            layout: Default::default(),
+            marked: Default::default(),
+            pending: Default::default(),
+            report: report.clone(),
         };
 
-        let runner = Runner {
+        let (cancel, cancelled) = watch::channel(());
+        let runner = Runner::<R> {
             rx,
-            client,
-            ctx: ctx.clone(),
+            client: registry.client().clone(),
+            redraw: registry.redraw().clone(),
+            report,
+            cancel: cancelled,
         };
 
-        let runner = spawn(async move {
+        RUNNERS.fetch_add(1, Ordering::Relaxed);
+        spawn(async move {
             runner.run().await;
+            RUNNERS.fetch_sub(1, Ordering::Relaxed);
         });
 
         Self {
-            _runner: runner,
+            _cancel: cancel,
             ctx,
         }
     }
@@ -175,8 +387,57 @@ where
         R::render(&self.ctx, r);
     }
 
-    pub async fn on_key(&self, key: Key) {
-        self.ctx.on_key(key).await;
+    pub async fn on_action(&self, action: Action) {
+        self.ctx.on_action(action).await;
+    }
+
+    /// Select the clicked row, sort by the clicked column header, or scroll.
+    pub async fn on_mouse(&self, mouse: Mouse) {
+        match mouse.kind {
+            MouseKind::ScrollUp => self.ctx.on_action(Action::Up).await,
+            MouseKind::ScrollDown => self.ctx.on_action(Action::Down).await,
+            MouseKind::Click => self.ctx.on_click(mouse.column, mouse.row),
+        }
+    }
+
+    /// All currently shown items, in the order they are shown.
+    pub fn items(&self) -> Vec<Arc<R::Resource>> {
+        self.ctx.items()
+    }
+
+    /// Only show items matching the filter, or all items when `None`.
+    pub fn set_filter(&self, filter: Option<Filter<R::Resource>>) {
+        *self.ctx.filter.lock().unwrap() = filter;
+    }
+
+    pub fn with_filter(self, filter: Filter<R::Resource>) -> Self {
+        self.set_filter(Some(filter));
+        self
+    }
+
+    /// The name of the active filter, if any.
+    pub fn filter(&self) -> Option<String> {
+        self.ctx
+            .filter
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(|f| f.name.clone())
+    }
+
+    /// The currently selected item, if any.
+    pub fn selected(&self) -> Option<Arc<R::Resource>> {
+        self.ctx.selected()
+    }
+
+    /// The currently selected item, in its generic form.
+    pub fn selected_object(&self) -> Option<serde_json::Result<Object>> {
+        self.selected().map(|selected| {
+            Ok(Object {
+                resource: ApiResource::erase::<R::Resource>(&Default::default()),
+                manifest: serde_json::to_value(selected.as_ref())?,
+            })
+        })
     }
 }
 
@@ -184,93 +445,348 @@ impl<R: ListResource> Context<R>
 where
     <<R as ListResource>::Resource as kube::Resource>::DynamicType: Hash + Eq + Clone,
 {
-    pub async fn on_key(&self, key: Key) {
-        match &mut (*self.state.lock().unwrap()) {
-            State::List(items, state) => {
-                let items = items.state();
-                match key {
-                    Key::Down => state.next(items.len(), 1),
-                    Key::Up => state.prev(items.len(), 1),
-                    Key::PageDown => state.next(items.len(), 20),
-                    Key::PageUp => state.prev(items.len(), 20),
-                    _ => {
-                        if let Some(msg) = R::on_key(items.as_slice(), state, key) {
-                            let _ = self.tx.try_send(msg);
+    /// The items of the store passing the filter, in the order they are shown.
+    fn visible(&self, store: &Store<R::Resource>) -> Vec<Arc<R::Resource>> {
+        let mut items = store.state();
+        if let Some(filter) = &*self.filter.lock().unwrap() {
+            items.retain(|item| filter.matches(item));
+        }
+        R::sort(&mut items);
+        if let Some(sort) = *self.sort.lock().unwrap() {
+            self.columns.sort(R::COLUMNS, &mut items, R::make_row, sort);
+        }
+        items
+    }
+
+    /// The title of the list, including the active filter and the number of marked items.
+    fn title(&self, marked: usize) -> String {
+        let mut details = vec![];
+        if let Some(filter) = &*self.filter.lock().unwrap() {
+            details.push(filter.name.clone());
+        }
+        if marked > 0 {
+            details.push(format!("{marked} marked"));
+        }
+
+        match details.is_empty() {
+            true => R::TITLE.to_string(),
+            false => format!("{} ({})", R::TITLE, details.join(", ")),
+        }
+    }
+
+    fn marked(&self) -> BTreeSet<String> {
+        self.marked.lock().unwrap().clone()
+    }
+
+    /// The targets of an action: the marked items, or else the selected one.
+    fn targets(&self, items: &[Arc<R::Resource>], state: &TableState) -> Vec<Arc<R::Resource>> {
+        let marked = self.marked.lock().unwrap();
+        let targets: Vec<_> = items
+            .iter()
+            .filter(|item| marked.contains(&item.name_any()))
+            .cloned()
+            .collect();
+
+        match targets.is_empty() {
+            true => state
+                .selected()
+                .and_then(|i| items.get(i))
+                .cloned()
+                .into_iter()
+                .collect(),
+            false => targets,
+        }
+    }
+
+    pub fn items(&self) -> Vec<Arc<R::Resource>> {
+        match &*self.watcher.state.lock().unwrap() {
+            State::List(items) => self.visible(items),
+            _ => vec![],
+        }
+    }
+
+    pub fn selected(&self) -> Option<Arc<R::Resource>> {
+        match &*self.watcher.state.lock().unwrap() {
+            State::List(items) => {
+                let items = self.visible(items);
+                let state = self.table.lock().unwrap();
+                state.selected().and_then(|i| items.get(i).cloned())
+            }
+            _ => None,
+        }
+    }
+
+    fn on_click(&self, x: u16, y: u16) {
+        let Some(layout) = self.layout.lock().unwrap().clone() else {
+            return;
+        };
+        let inner = layout.area.inner(&Margin {
+            vertical: 1,
+            horizontal: 1,
+        });
+        if x < inner.x || x >= inner.right() || y < inner.y || y >= inner.bottom() {
+            return;
+        }
+
+        if y == inner.y {
+            // the header, each column also covering the space to the next one
+            let mut right = inner.x;
+            if layout.highlight {
+                right += HIGHLIGHT_SYMBOL.len() as u16;
+            }
+            let column = layout.widths.iter().position(|width| {
+                right = right.saturating_add(width + 1);
+                x < right
+            });
+
+            if let Some(column) = column {
+                let mut sort = self.sort.lock().unwrap();
+                *sort = Sort::toggle(*sort, column);
+            }
+        } else {
+            let count = self.items().len();
+            let mut state = self.table.lock().unwrap();
+            let index = state.offset() + usize::from(y - inner.y - 1);
+            if index < count {
+                state.select(Some(index));
+            }
+        }
+    }
+
+    pub async fn on_action(&self, action: Action) {
+        if let State::List(items) = &*self.watcher.state.lock().unwrap() {
+            let items = self.visible(items);
+            let state = &mut *self.table.lock().unwrap();
+            match action {
+                Action::Down => state.next(items.len(), 1),
+                Action::Up => state.prev(items.len(), 1),
+                Action::PageDown => state.next(items.len(), 20),
+                Action::PageUp => state.prev(items.len(), 20),
+                Action::First if !items.is_empty() => state.select(Some(0)),
+                Action::Last if !items.is_empty() => state.select(Some(items.len() - 1)),
+                Action::ToggleMark => {
+                    if let Some(item) = state.selected().and_then(|i| items.get(i)) {
+                        let mut marked = self.marked.lock().unwrap();
+                        let name = item.name_any();
+                        if !marked.remove(&name) {
+                            marked.insert(name);
                         }
+                        state.next(items.len(), 1);
+                    }
+                }
+                Action::MarkAll => {
+                    let mut marked = self.marked.lock().unwrap();
+                    let names = items.iter().map(|item| item.name_any());
+                    if items.iter().all(|item| marked.contains(&item.name_any())) {
+                        names.for_each(|name| {
+                            marked.remove(&name);
+                        });
+                    } else {
+                        marked.extend(names);
+                    }
+                }
+                _ => {
+                    let targets = self.targets(&items, state);
+                    let (task, delete) = if R::deletes(action) && !targets.is_empty() {
+                        let terminating = targets
+                            .iter()
+                            .all(|item| item.meta().deletion_timestamp.is_some());
+                        let dialog = DeleteDialog::new(R::EVICTABLE, terminating)
+                            .with_dependents(R::dependents(&targets));
+                        let task = Task::Delete(targets.clone(), dialog.options);
+                        (task, Some(dialog))
+                    } else if let Some(msg) = R::on_action(&targets, action) {
+                        (Task::Message(msg), None)
+                    } else {
+                        return;
+                    };
+                    let job = Job {
+                        label: label(action),
+                        task,
+                    };
+
+                    if targets.len() > 1 || delete.is_some() {
+                        let objects: Vec<_> = targets.iter().map(|item| item.name_any()).collect();
+                        let confirmation = Confirmation {
+                            title: match objects.as_slice() {
+                                [name] => format!("{} {name}", job.label),
+                                _ => format!(
+                                    "{} {} {}",
+                                    job.label,
+                                    objects.len(),
+                                    R::TITLE.to_lowercase()
+                                ),
+                            },
+                            objects,
+                            delete,
+                        };
+                        *self.pending.lock().unwrap() = Some((confirmation, job));
+                    } else {
+                        let _ = self.tx.try_send(job);
                     }
                 }
             }
-            _ => {}
         }
     }
 }
 
-impl<R> Runner<R>
+/// Delete or evict objects, one after the other.
+async fn delete_all<K>(
+    client: Arc<Client>,
+    targets: Vec<Arc<K>>,
+    options: DeleteOptions,
+) -> Vec<Outcome>
+where
+    K: kube::Resource<Scope = NamespaceResourceScope> + Clone + Debug + DeserializeOwned,
+    K::DynamicType: Default,
+{
+    let mut outcomes = vec![];
+    for target in targets {
+        let result = delete(&client, target.as_ref(), options).await;
+        outcomes.push(Outcome::new(target.name_any(), result));
+    }
+    outcomes
+}
+
+/// What an action does, like "Scale up".
+fn label(action: Action) -> String {
+    let hint = action.hint().unwrap_or("apply");
+    let mut chars = hint.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// The state of a list which doesn't depend on its type of resource, like pending confirmations.
+pub trait ListControl {
+    /// The bulk action waiting to be confirmed, if any.
+    fn confirmation(&self) -> Option<Confirmation>;
+
+    /// Run the pending bulk action (clearing the marks), or drop it.
+    fn confirm(&self, confirmed: bool);
+
+    /// Change an option of the pending action, if it has any.
+    fn on_confirmation_action(&self, action: Action);
+
+    /// The outcomes of the last bulk action, until dismissed.
+    fn report(&self) -> Option<Report>;
+
+    fn dismiss_report(&self);
+
+    /// Unmark all items, returning `false` if none were marked.
+    fn clear_marks(&self) -> bool;
+
+    /// Whether a confirmation or report is shown on top of the list.
+    fn has_popup(&self) -> bool {
+        self.confirmation().is_some() || self.report().is_some()
+    }
+}
+
+impl<R> ListControl for ListWatcher<R>
 where
     R: ListResource,
-    <<R as ListResource>::Resource as kube::Resource>::DynamicType: Hash + Eq + Clone + Default,
+    <<R as ListResource>::Resource as kube::Resource>::DynamicType: Hash + Eq,
+{
+    fn confirmation(&self) -> Option<Confirmation> {
+        let pending = self.ctx.pending.lock().unwrap();
+        pending
+            .as_ref()
+            .map(|(confirmation, _)| confirmation.clone())
+    }
+
+    fn confirm(&self, confirmed: bool) {
+        let Some((confirmation, mut job)) = self.ctx.pending.lock().unwrap().take() else {
+            return;
+        };
+        if let (Task::Delete(_, options), Some(dialog)) = (&mut job.task, confirmation.delete) {
+            *options = dialog.options;
+        }
+        if confirmed {
+            self.ctx.marked.lock().unwrap().clear();
+            let _ = self.ctx.tx.try_send(job);
+        }
+    }
+
+    fn on_confirmation_action(&self, action: Action) {
+        let mut pending = self.ctx.pending.lock().unwrap();
+        if let Some(dialog) = pending.as_mut().and_then(|(c, _)| c.delete.as_mut()) {
+            dialog.on_action(action);
+        }
+    }
+
+    fn report(&self) -> Option<Report> {
+        self.ctx.report.lock().unwrap().clone()
+    }
+
+    fn dismiss_report(&self) {
+        *self.ctx.report.lock().unwrap() = None;
+    }
+
+    fn clear_marks(&self) -> bool {
+        let mut marked = self.ctx.marked.lock().unwrap();
+        let any = !marked.is_empty();
+        marked.clear();
+        any
+    }
+}
+
+impl<R: ListResource> Runner<R>
+where
+    <<R as ListResource>::Resource as kube::Resource>::DynamicType: Default,
 {
+    /// Process messages until the list is dropped.
+    ///
+    /// Queued messages are discarded then, an in-flight action gets a grace period to complete.
     async fn run(mut self) {
-        let client = self.client.clone();
-        let ctx = self.ctx.clone();
-
-        let reflector = async {
-            let mut reflector: Option<Result<Reflector<R::Resource>, anyhow::Error>> = None;
-
-            'outer: loop {
-                match reflector {
-                    None => {
-                        *ctx.state.lock().unwrap() = State::Loading;
-                        // Create
-                        reflector = Some(Reflector::new(&client).await);
-                    }
-                    Some(Err(err)) => {
-                        // set error
-                        {
-                            *ctx.state.lock().unwrap() = State::Error(anyhow!(err));
-                        }
-                        // create
-                        let r = Reflector::new(&client).await;
-                        log::warn!("Created new reflector - ok: {}", r.is_ok());
-                        reflector = Some(r);
-                    }
-                    // FIXME: need to deal with the case that we could create a watcher, but it
-                    // right away fails. Which results in a red blinking display.
-                    Some(Ok(mut r)) => {
-                        // set store
-                        {
-                            *ctx.state.lock().unwrap() =
-                                State::List(r.reader.clone(), Default::default());
-                        }
-                        // run
-                        while let Some(evt) = r.stream.next().await {
-                            if log_enabled!(log::Level::Info) {
-                                let m = format!("{evt:?}");
-                                log::info!("{}", &m[0..90]);
-                            }
-                            match evt {
-                                Ok(_) => {}
-                                Err(err) => {
-                                    log::warn!("Watch error: {err}");
-                                    reflector = Some(Err(anyhow!(err)));
-                                    continue 'outer;
-                                }
-                            }
-                        }
-                        log::warn!("Stream closed");
-                        reflector = Some(Err(anyhow!("Stream closed")));
+        let client = Arc::new(self.client.clone());
+
+        loop {
+            let job = tokio::select! {
+                biased;
+                _ = self.cancel.changed() => break,
+                job = self.rx.recv() => match job {
+                    Some(job) => job,
+                    None => break,
+                },
+            };
+
+            let mut process = match job.task {
+                Task::Message(msg) => R::process(client.clone(), msg),
+                Task::Delete(targets, options) => {
+                    Box::pin(delete_all(client.clone(), targets, options))
+                }
+            };
+            tokio::select! {
+                outcomes = &mut process => {
+                    self.report(&job.label, outcomes);
+                    self.redraw.request();
+                }
+                _ = self.cancel.changed() => {
+                    if timeout(CANCEL_GRACE_PERIOD, process).await.is_err() {
+                        log::warn!("Cancelled pending action on {}", R::TITLE);
                     }
+                    break;
                 }
             }
-        };
+        }
+    }
 
-        let receiver = async {
-            let client = Arc::new(client.clone());
-            while let Some(msg) = self.rx.recv().await {
-                R::process(client.clone(), msg).await;
+    /// Log the outcomes, and keep them to be shown if there are several, or any failed.
+    fn report(&self, label: &str, outcomes: Vec<Outcome>) {
+        for outcome in &outcomes {
+            match &outcome.error {
+                None => log::info!("{label} {}: done", outcome.name),
+                Some(err) => log::warn!("{label} {} failed: {err}", outcome.name),
             }
-        };
+        }
 
-        futures::future::select(Box::pin(reflector), Box::pin(receiver)).await;
+        let title = match outcomes.as_slice() {
+            [] => return,
+            [outcome] if outcome.error.is_some() => format!("{label} {}", outcome.name),
+            [_] => return,
+            _ => format!("{label} {} {}", outcomes.len(), R::TITLE.to_lowercase()),
+        };
+        *self.report.lock().unwrap() = Some(Report { title, outcomes });
     }
 }
@@ -1,45 +1,249 @@
+pub mod columns;
+pub mod config;
+pub mod delete;
 mod deployments;
+mod events;
 pub mod list;
+mod overview;
 mod pods;
+pub mod registry;
+pub mod tabs;
 
+pub use deployments::Deployments;
+pub use events::Events;
+pub use overview::*;
 pub use pods::*;
 
-use crate::app::state::deployments::Deployments;
-use crate::app::state::list::ListWatcher;
-use crate::input::key::Key;
+use crate::app::state::columns::ColumnConfig;
+use crate::app::state::config::{ConfigMaps, ConfigView, Secrets};
+use crate::app::state::list::{ListControl, ListResource, ListWatcher, Object};
+use crate::app::state::registry::Registry;
+use crate::input::keymap::{Action, Scope};
+use crate::input::mouse::Mouse;
 use crate::ui::StateRenderer;
 use k8s_openapi::api::core::v1::Pod;
 
+/// The available views
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, clap::ValueEnum, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum View {
+    Overview,
+    Pods,
+    Deployments,
+    Events,
+    #[value(alias = "configmaps")]
+    #[serde(alias = "configmaps")]
+    ConfigMaps,
+    Secrets,
+}
+
+impl View {
+    pub const ALL: [View; 6] = [
+        View::Overview,
+        View::Pods,
+        View::Deployments,
+        View::Events,
+        View::ConfigMaps,
+        View::Secrets,
+    ];
+
+    /// The built-in columns of the view
+    pub fn columns(&self) -> &'static [&'static str] {
+        match self {
+            Self::Overview => &[],
+            Self::Pods => Pod::COLUMNS,
+            Self::Deployments => Deployments::COLUMNS,
+            Self::Events => Events::COLUMNS,
+            Self::ConfigMaps => ConfigMaps::COLUMNS,
+            Self::Secrets => Secrets::COLUMNS,
+        }
+    }
+
+    /// The title of the view, as shown in its tab
+    pub fn title(&self) -> &'static str {
+        match self {
+            Self::Overview => "Overview",
+            Self::Pods => Pod::TITLE,
+            Self::Deployments => Deployments::TITLE,
+            Self::Events => Events::TITLE,
+            Self::ConfigMaps => ConfigMaps::TITLE,
+            Self::Secrets => Secrets::TITLE,
+        }
+    }
+}
+
+#[derive(Default)]
 pub enum AppState {
+    #[default]
     Initializing,
-    Pods(ListWatcher<Pod>),
+    Overview(Box<Overview>),
+    Pods(PodView),
     Deployments(ListWatcher<Deployments>),
+    Events(ListWatcher<Events>),
+    ConfigMaps(ConfigView<ConfigMaps>),
+    Secrets(ConfigView<Secrets>),
 }
 
 impl AppState {
+    pub fn new(view: View, registry: &Registry, columns: ColumnConfig) -> Self {
+        match view {
+            View::Overview => Self::Overview(Box::new(Overview::new(registry))),
+            View::Pods => Self::Pods(PodView::new(registry, columns)),
+            View::Deployments => Self::Deployments(ListWatcher::new(registry, columns)),
+            View::Events => Self::Events(ListWatcher::new(registry, columns)),
+            View::ConfigMaps => Self::ConfigMaps(ConfigView::new(registry, columns)),
+            View::Secrets => Self::Secrets(ConfigView::new(registry, columns)),
+        }
+    }
+
+    /// The view currently shown
+    pub fn view(&self) -> Option<View> {
+        match self {
+            Self::Initializing => None,
+            Self::Overview(_) => Some(View::Overview),
+            Self::Pods(_) => Some(View::Pods),
+            Self::Deployments(_) => Some(View::Deployments),
+            Self::Events(_) => Some(View::Events),
+            Self::ConfigMaps(_) => Some(View::ConfigMaps),
+            Self::Secrets(_) => Some(View::Secrets),
+        }
+    }
+
     pub fn render<R: StateRenderer>(&self, r: R) {
         match self {
+            Self::Overview(overview) => overview.render(r),
             Self::Pods(pods) => pods.render(r),
             Self::Deployments(deployments) => deployments.render(r),
+            Self::Events(events) => events.render(r),
+            Self::ConfigMaps(configmaps) => configmaps.render(r),
+            Self::Secrets(secrets) => secrets.render(r),
             _ => {}
         }
     }
 
-    pub async fn on_key(&self, key: Key) {
+    /// The currently selected object, if any.
+    pub fn selected_object(&self) -> Option<serde_json::Result<Object>> {
+        match self {
+            Self::Initializing | Self::Overview(_) => None,
+            Self::Pods(pods) => pods.selected_object(),
+            Self::Deployments(deployments) => deployments.selected_object(),
+            Self::Events(events) => events.selected_object(),
+            Self::ConfigMaps(configmaps) => configmaps.selected_object(),
+            Self::Secrets(secrets) => secrets.selected_object(),
+        }
+    }
+
+    /// The list shown, if any.
+    pub fn list(&self) -> Option<&dyn ListControl> {
+        match self {
+            Self::Initializing | Self::Overview(_) => None,
+            Self::Pods(pods) => Some(pods.list()),
+            Self::Deployments(deployments) => Some(deployments),
+            Self::Events(events) => Some(events),
+            Self::ConfigMaps(configmaps) => Some(configmaps.list()),
+            Self::Secrets(secrets) => Some(secrets.list()),
+        }
+    }
+
+    /// The number of objects shown, after filtering.
+    pub fn count(&self) -> Option<usize> {
+        match self {
+            Self::Initializing | Self::Overview(_) => None,
+            Self::Pods(pods) => Some(pods.items().len()),
+            Self::Deployments(deployments) => Some(deployments.items().len()),
+            Self::Events(events) => Some(events.items().len()),
+            Self::ConfigMaps(configmaps) => Some(configmaps.items().len()),
+            Self::Secrets(secrets) => Some(secrets.items().len()),
+        }
+    }
+
+    /// The key binding scopes of the view, in order of precedence.
+    pub fn scopes(&self) -> Vec<Scope> {
+        match self {
+            Self::Initializing => vec![Scope::Global],
+            Self::Overview(_) => vec![Scope::Overview, Scope::List, Scope::Global],
+            Self::Pods(_) => vec![Scope::Pods, Scope::List, Scope::Global],
+            Self::Deployments(_) => vec![Scope::Deployments, Scope::List, Scope::Global],
+            Self::Events(_) => vec![Scope::List, Scope::Global],
+            Self::ConfigMaps(_) => vec![Scope::List, Scope::Global],
+            Self::Secrets(_) => vec![Scope::Secrets, Scope::List, Scope::Global],
+        }
+    }
+
+    pub async fn on_action(&mut self, action: Action) {
         match self {
+            Self::Overview(overview) => {
+                overview.on_action(action).await;
+            }
             Self::Pods(pods) => {
-                pods.on_key(key).await;
+                pods.on_action(action).await;
             }
             Self::Deployments(deployments) => {
-                deployments.on_key(key).await;
+                deployments.on_action(action).await;
+            }
+            Self::Events(events) => {
+                events.on_action(action).await;
+            }
+            Self::ConfigMaps(configmaps) => {
+                configmaps.on_action(action).await;
+            }
+            Self::Secrets(secrets) => {
+                secrets.on_action(action).await;
             }
             _ => {}
         }
     }
-}
 
-impl Default for AppState {
-    fn default() -> Self {
-        Self::Initializing
+    pub async fn on_mouse(&mut self, mouse: Mouse) {
+        match self {
+            Self::Overview(overview) => overview.on_mouse(mouse).await,
+            Self::Pods(pods) => pods.on_mouse(mouse).await,
+            Self::Deployments(deployments) => deployments.on_mouse(mouse).await,
+            Self::Events(events) => events.on_mouse(mouse).await,
+            Self::ConfigMaps(configmaps) => configmaps.on_mouse(mouse).await,
+            Self::Secrets(secrets) => secrets.on_mouse(mouse).await,
+            Self::Initializing => {}
+        }
+    }
+
+    /// Only show items matching the filter of the target, if it matches the current view.
+    pub fn set_filter(&mut self, target: Target) {
+        match (self, target) {
+            (Self::Pods(pods), Target::Pods(filter)) => pods.set_filter(filter),
+            (Self::Deployments(deployments), Target::Deployments(filter)) => {
+                deployments.set_filter(filter)
+            }
+            (Self::Events(events), Target::Events(filter)) => events.set_filter(filter),
+            _ => {}
+        }
+    }
+
+    /// Remove the filter of the current list, returning `false` if there was none.
+    pub fn clear_filter(&mut self) -> bool {
+        let filter = match self {
+            Self::Pods(pods) => pods.filter(),
+            Self::Deployments(deployments) => deployments.filter(),
+            Self::Events(events) => events.filter(),
+            _ => None,
+        };
+        if filter.is_none() {
+            return false;
+        }
+
+        match self {
+            Self::Pods(pods) => pods.set_filter(None),
+            Self::Deployments(deployments) => deployments.set_filter(None),
+            Self::Events(events) => events.set_filter(None),
+            _ => {}
+        }
+        true
+    }
+
+    /// The target of the selected tile, when showing the overview.
+    pub fn target(&self) -> Option<Target> {
+        match self {
+            Self::Overview(overview) => Some(overview.target()),
+            _ => None,
+        }
     }
 }
@@ -0,0 +1,342 @@
+use crate::{
+    app::state::{
+        all_containers,
+        list::{Filter, ListResource, ListWatcher},
+        make_restarts, pod_state,
+        registry::Registry,
+        state_style, Deployments, Events, PodState, View,
+    },
+    input::{
+        keymap::Action,
+        mouse::{Mouse, MouseKind},
+    },
+    k8s::{ago, format_bytes, format_cpu, parse_quantity},
+    ui::{theme::theme, StateRenderer},
+};
+use chrono::Utc;
+use k8s_openapi::{
+    api::{
+        apps::v1::Deployment,
+        core::v1::{Event, Pod},
+    },
+    apimachinery::pkg::apis::meta::v1::Time,
+};
+use kube::ResourceExt;
+use ratatui::{layout::*, style::*, text::*, widgets::*};
+use std::{
+    cmp::Reverse,
+    collections::BTreeMap,
+    sync::{Arc, Mutex},
+};
+
+/// A tile of the overview
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Tile {
+    Pods,
+    Deployments,
+    Resources,
+    Events,
+    Restarts,
+}
+
+impl Tile {
+    const ALL: [Tile; 5] = [
+        Tile::Pods,
+        Tile::Deployments,
+        Tile::Resources,
+        Tile::Events,
+        Tile::Restarts,
+    ];
+}
+
+/// A list to jump to, with an optional filter.
+pub enum Target {
+    Pods(Option<Filter<Pod>>),
+    Deployments(Option<Filter<Deployment>>),
+    Events(Option<Filter<Event>>),
+}
+
+impl Target {
+    pub fn view(&self) -> View {
+        match self {
+            Self::Pods(_) => View::Pods,
+            Self::Deployments(_) => View::Deployments,
+            Self::Events(_) => View::Events,
+        }
+    }
+}
+
+/// A summary of the namespace, aggregated from the pods, deployments and events.
+pub struct Overview {
+    pods: ListWatcher<Pod>,
+    deployments: ListWatcher<Deployments>,
+    events: ListWatcher<Events>,
+    selected: usize,
+    /// Where the tiles were rendered last, to find the clicked one
+    areas: Mutex<Vec<Rect>>,
+}
+
+impl Overview {
+    pub fn new(registry: &Registry) -> Self {
+        Self {
+            pods: ListWatcher::new(registry, Default::default()),
+            deployments: ListWatcher::new(registry, Default::default()),
+            events: ListWatcher::new(registry, Default::default()),
+            selected: 0,
+            areas: Default::default(),
+        }
+    }
+
+    pub fn selected(&self) -> Tile {
+        Tile::ALL[self.selected]
+    }
+
+    /// The list behind the selected tile.
+    pub fn target(&self) -> Target {
+        match self.selected() {
+            Tile::Pods => Target::Pods(None),
+            Tile::Deployments => Target::Deployments(Some(Filter::new("not available", |d| {
+                !Deployments::is_available(d)
+            }))),
+            Tile::Resources => Target::Pods(Some(Filter::new("requesting resources", |p| {
+                requests(p).is_some()
+            }))),
+            Tile::Events => Target::Events(Some(Filter::new("recent warnings", recent_warning))),
+            Tile::Restarts => Target::Pods(Some(Filter::new("recently restarted", restarted))),
+        }
+    }
+
+    pub fn render<SR: StateRenderer>(&self, mut r: SR) {
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+            .split(r.rect());
+        let top = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Ratio(1, 3); 3])
+            .split(rows[0]);
+        let bottom = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
+            .split(rows[1]);
+
+        let pods = self.pods.items();
+        let rects = [top[0], top[1], top[2], bottom[0], bottom[1]];
+        *self.areas.lock().unwrap() = rects.to_vec();
+
+        for (tile, rect) in Tile::ALL.into_iter().zip(rects) {
+            let (title, lines) = match tile {
+                Tile::Pods => pods_tile(&pods),
+                Tile::Deployments => deployments_tile(&self.deployments.items()),
+                Tile::Resources => resources_tile(&pods),
+                Tile::Events => events_tile(&self.events.items()),
+                Tile::Restarts => restarts_tile(&pods),
+            };
+
+            let mut block = Block::default().borders(Borders::ALL).title(title);
+            if tile == self.selected() {
+                block = block
+                    .border_type(BorderType::Thick)
+                    .border_style(theme().header);
+            }
+
+            r.render_child(Paragraph::new(lines).block(block), rect);
+        }
+    }
+
+    pub async fn on_action(&mut self, action: Action) {
+        let last = Tile::ALL.len() - 1;
+        match action {
+            Action::Down | Action::PageDown => self.selected = (self.selected + 1).min(last),
+            Action::Up | Action::PageUp => self.selected = self.selected.saturating_sub(1),
+            Action::First => self.selected = 0,
+            Action::Last => self.selected = last,
+            _ => {}
+        }
+    }
+
+    /// Select the clicked tile, or move the selection by scrolling.
+    pub async fn on_mouse(&mut self, mouse: Mouse) {
+        match mouse.kind {
+            MouseKind::ScrollUp => self.on_action(Action::Up).await,
+            MouseKind::ScrollDown => self.on_action(Action::Down).await,
+            MouseKind::Click => {
+                let (x, y) = (mouse.column, mouse.row);
+                let clicked = self.areas.lock().unwrap().iter().position(|area| {
+                    (area.left()..area.right()).contains(&x)
+                        && (area.top()..area.bottom()).contains(&y)
+                });
+                if let Some(clicked) = clicked {
+                    self.selected = clicked;
+                }
+            }
+        }
+    }
+}
+
+/// Check if something happened within the last hour
+fn recent(time: &Time) -> bool {
+    Utc::now() - time.0 < chrono::Duration::hours(1)
+}
+
+pub(super) fn recent_warning(event: &Event) -> bool {
+    Events::is_warning(event) && Events::last_seen(event).is_some_and(|t| recent(&t))
+}
+
+/// Check if a container of the pod was restarted within the last hour
+fn restarted(pod: &Pod) -> bool {
+    pod.status
+        .iter()
+        .flat_map(all_containers)
+        .filter(|c| c.restart_count > 0)
+        .filter_map(|c| {
+            c.last_state
+                .as_ref()?
+                .terminated
+                .as_ref()?
+                .finished_at
+                .as_ref()
+        })
+        .any(recent)
+}
+
+/// The CPU (in cores) and memory (in bytes) requested by a pod which is not yet finished.
+fn requests(pod: &Pod) -> Option<(f64, f64)> {
+    if matches!(
+        pod.status.as_ref().and_then(|s| s.phase.as_deref()),
+        Some("Succeeded" | "Failed")
+    ) {
+        return None;
+    }
+
+    let requests = pod
+        .spec
+        .iter()
+        .flat_map(|spec| &spec.containers)
+        .filter_map(|c| c.resources.as_ref()?.requests.as_ref())
+        .collect::<Vec<_>>();
+    if requests.is_empty() {
+        return None;
+    }
+
+    let sum = |name: &str| {
+        requests
+            .iter()
+            .filter_map(|r| r.get(name))
+            .filter_map(parse_quantity)
+            .fold(0.0, |sum, value| sum + value)
+    };
+    Some((sum("cpu"), sum("memory")))
+}
+
+fn count(title: &str, count: usize) -> String {
+    format!("{title} ({count})")
+}
+
+fn pods_tile<'a>(pods: &[Arc<Pod>]) -> (String, Vec<Line<'a>>) {
+    let mut states = BTreeMap::<_, (usize, PodState)>::new();
+    for pod in pods {
+        let state = pod_state(pod);
+        let entry = states
+            .entry((Reverse(state.severity()), state.to_string()))
+            .or_insert((0, state));
+        entry.0 += 1;
+    }
+
+    let lines = states
+        .into_iter()
+        .map(|((_, name), (count, state))| {
+            Line::styled(format!("{count:>4}  {name}"), state_style(&state))
+        })
+        .collect();
+
+    (count("Pods", pods.len()), lines)
+}
+
+fn deployments_tile<'a>(deployments: &[Arc<Deployment>]) -> (String, Vec<Line<'a>>) {
+    let unavailable = deployments
+        .iter()
+        .filter(|d| !Deployments::is_available(d))
+        .collect::<Vec<_>>();
+
+    let lines = unavailable
+        .iter()
+        .map(|d| {
+            let row = <Deployments as ListResource>::make_row(d);
+            let ready = row.cells.get(1).cloned().unwrap_or_default();
+            Line::styled(format!("{ready:>7}  {}", d.name_any()), row.style)
+        })
+        .collect();
+
+    (count("Unavailable", unavailable.len()), lines)
+}
+
+fn resources_tile<'a>(pods: &[Arc<Pod>]) -> (String, Vec<Line<'a>>) {
+    let requests = pods.iter().filter_map(|p| requests(p)).collect::<Vec<_>>();
+    let cpu = requests.iter().fold(0.0, |sum, r| sum + r.0);
+    let memory = requests.iter().fold(0.0, |sum, r| sum + r.1);
+
+    let bold = Style::default().add_modifier(Modifier::BOLD);
+    let lines = vec![
+        Line::from(vec![
+            Span::styled("CPU:     ", bold),
+            Span::from(format_cpu(cpu)),
+        ]),
+        Line::from(vec![
+            Span::styled("Memory:  ", bold),
+            Span::from(format_bytes(memory)),
+        ]),
+        Line::from(vec![
+            Span::styled("Pods:    ", bold),
+            Span::from(requests.len().to_string()),
+        ]),
+    ];
+
+    ("Requests".into(), lines)
+}
+
+fn events_tile<'a>(events: &[Arc<Event>]) -> (String, Vec<Line<'a>>) {
+    let warnings = events
+        .iter()
+        .filter(|e| recent_warning(e))
+        .collect::<Vec<_>>();
+
+    let lines = warnings
+        .iter()
+        .map(|e| {
+            let when = Events::last_seen(e)
+                .as_ref()
+                .and_then(ago)
+                .unwrap_or_default();
+            Line::styled(
+                format!(
+                    "{when:>4}  {}  {}: {}",
+                    e.reason.as_deref().unwrap_or_default(),
+                    Events::object(e),
+                    e.message.as_deref().unwrap_or_default().trim()
+                ),
+                theme().pod_warning,
+            )
+        })
+        .collect();
+
+    (count("Recent warnings", warnings.len()), lines)
+}
+
+fn restarts_tile<'a>(pods: &[Arc<Pod>]) -> (String, Vec<Line<'a>>) {
+    let restarted = pods.iter().filter(|p| restarted(p)).collect::<Vec<_>>();
+
+    let lines = restarted
+        .iter()
+        .map(|pod| {
+            let restarts = pod
+                .status
+                .as_ref()
+                .and_then(make_restarts)
+                .unwrap_or_default();
+            Line::from(format!("{}  {restarts}", pod.name_any()))
+        })
+        .collect();
+
+    (count("Recent restarts", restarted.len()), lines)
+}
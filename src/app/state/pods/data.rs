@@ -1,34 +1,155 @@
 use crate::k8s::ago;
 use chrono::Utc;
 use k8s_openapi::{
-    api::core::v1::{ContainerState, ContainerStatus, PodStatus},
+    api::core::v1::{ContainerState, ContainerStatus, PodCondition, PodStatus},
     apimachinery::pkg::apis::meta::v1::Time,
 };
 use std::fmt::{Display, Formatter};
 
-#[derive(Clone, Debug)]
+/// How bad a pod state is, from good to worst.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    Ok,
+    Progressing,
+    Warning,
+    Error,
+    Critical,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
 pub enum PodState {
     Pending,
     ContainerCreating,
     PodInitializing,
     Running,
+    Completed,
+    Terminating,
     Error,
+    Failed,
     CrashLoopBackOff,
-    Terminating,
+    ImagePullBackOff,
+    ErrImagePull,
+    ErrImageNeverPull,
+    InvalidImageName,
+    OOMKilled,
+    CreateContainerConfigError,
+    CreateContainerError,
+    RunContainerError,
+    ContainerCannotRun,
+    ContainerStatusUnknown,
+    PreStartHookError,
+    PostStartHookError,
+    DeadlineExceeded,
+    Evicted,
+    NodeLost,
+    Unschedulable,
+    /// The state of a failing or waiting init container
+    Init(Box<PodState>),
+    /// A container terminated without a reason
+    ExitCode(i32),
+    /// A container was terminated by a signal, without a reason
+    Signal(i32),
+    #[default]
     Unknown,
     Other(String),
 }
 
+/// Map between simple states and their reason, which is the name of the variant.
+macro_rules! reasons {
+    ($($variant:ident),* $(,)?) => {
+        impl PodState {
+            fn from_reason(reason: &str) -> Option<Self> {
+                match reason {
+                    $(stringify!($variant) => Some(Self::$variant),)*
+                    _ => None,
+                }
+            }
+
+            fn reason(&self) -> Option<&'static str> {
+                match self {
+                    $(Self::$variant => Some(stringify!($variant)),)*
+                    _ => None,
+                }
+            }
+        }
+    };
+}
+
+reasons!(
+    Pending,
+    ContainerCreating,
+    PodInitializing,
+    Running,
+    Completed,
+    Terminating,
+    Error,
+    Failed,
+    CrashLoopBackOff,
+    ImagePullBackOff,
+    ErrImagePull,
+    ErrImageNeverPull,
+    InvalidImageName,
+    OOMKilled,
+    CreateContainerConfigError,
+    CreateContainerError,
+    RunContainerError,
+    ContainerCannotRun,
+    ContainerStatusUnknown,
+    PreStartHookError,
+    PostStartHookError,
+    DeadlineExceeded,
+    Evicted,
+    NodeLost,
+    Unschedulable,
+);
+
+impl PodState {
+    pub fn severity(&self) -> Severity {
+        match self {
+            Self::Running | Self::Completed | Self::ExitCode(0) => Severity::Ok,
+            Self::Pending
+            | Self::ContainerCreating
+            | Self::PodInitializing
+            | Self::Terminating
+            | Self::Unknown => Severity::Progressing,
+            Self::ImagePullBackOff
+            | Self::ErrImagePull
+            | Self::ContainerStatusUnknown
+            | Self::NodeLost
+            | Self::Unschedulable
+            | Self::Other(_) => Severity::Warning,
+            Self::Error
+            | Self::Failed
+            | Self::ErrImageNeverPull
+            | Self::InvalidImageName
+            | Self::OOMKilled
+            | Self::CreateContainerConfigError
+            | Self::CreateContainerError
+            | Self::RunContainerError
+            | Self::ContainerCannotRun
+            | Self::PreStartHookError
+            | Self::PostStartHookError
+            | Self::DeadlineExceeded
+            | Self::Evicted
+            | Self::ExitCode(_)
+            | Self::Signal(_) => Severity::Error,
+            Self::CrashLoopBackOff => Severity::Critical,
+            Self::Init(state) => state.severity().max(Severity::Progressing),
+        }
+    }
+}
+
 impl From<&str> for PodState {
     fn from(reason: &str) -> Self {
         match reason {
-            "Pending" => Self::Pending,
-            "ContainerCreating" => Self::ContainerCreating,
-            "PodInitializing" => Self::PodInitializing,
-            "Running" => Self::Running,
-            "Error" => Self::Error,
-            "CrashLoopBackOff" => Self::CrashLoopBackOff,
-            reason => Self::Other(reason.to_string()),
+            // the phase of a successful pod
+            "Succeeded" => Self::Completed,
+            reason => match reason.strip_prefix("Init:") {
+                Some(reason) => Self::Init(Box::new(reason.into())),
+                None => {
+                    Self::from_reason(reason).unwrap_or_else(|| Self::Other(reason.to_string()))
+                }
+            },
         }
     }
 }
@@ -48,49 +169,70 @@ impl From<Option<&str>> for PodState {
     }
 }
 
-impl Default for PodState {
-    fn default() -> Self {
-        Self::Unknown
-    }
-}
-
 impl Display for PodState {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if let Some(reason) = self.reason() {
+            return f.write_str(reason);
+        }
+
         match self {
-            Self::Unknown => Ok(()),
-            Self::ContainerCreating => f.write_str("ContainerCreating"),
-            Self::PodInitializing => f.write_str("PodInitializing"),
-            Self::Running => f.write_str("Running"),
-            Self::Pending => f.write_str("Pending"),
-            Self::Error => f.write_str("Error"),
-            Self::CrashLoopBackOff => f.write_str("CrashLoopBackOff"),
-            Self::Terminating => f.write_str("Terminating"),
+            Self::Init(state) => write!(f, "Init:{state}"),
+            Self::ExitCode(code) => write!(f, "ExitCode:{code}"),
+            Self::Signal(signal) => write!(f, "Signal:{signal}"),
             Self::Other(state) => f.write_str(state),
+            _ => Ok(()),
         }
     }
 }
 
 pub fn make_state(status: &PodStatus) -> PodState {
+    // a reason of the pod itself, like being evicted
+    if let Some(reason) = &status.reason {
+        return reason.into();
+    }
+
+    // failing or waiting init containers
+    for container in status.init_container_statuses.iter().flatten() {
+        let completed = container
+            .state
+            .as_ref()
+            .and_then(|s| s.terminated.as_ref())
+            .is_some_and(|t| t.exit_code == 0);
+        if completed {
+            continue;
+        }
+        if let Some(state) = container_state(container) {
+            return PodState::Init(Box::new(state));
+        }
+    }
+
     // get all non-ready containers
 
-    let mut containers: Vec<_> =
-        with_last_changed(all_containers(status).filter(|c| !c.ready)).collect();
+    let mut containers: Vec<_> = with_last_changed(
+        status
+            .container_statuses
+            .iter()
+            .flatten()
+            .filter(|c| !c.ready),
+    )
+    .collect();
 
     // sort by latest change
 
-    containers.sort_unstable_by(|a, b| a.0 .0.cmp(&b.0 .0));
-    if let Some(container) = containers.first().map(|c| c.1) {
-        let reason = match (
-            container.state.as_ref().and_then(|s| s.waiting.as_ref()),
-            container.state.as_ref().and_then(|s| s.terminated.as_ref()),
-        ) {
-            (Some(waiting), _) => waiting.reason.as_ref(),
-            (_, Some(terminated)) => terminated.reason.as_ref(),
-            _ => None,
-        };
-        if let Some(reason) = reason {
-            return reason.into();
-        }
+    containers.sort_unstable_by_key(|a| a.0 .0);
+    if let Some(state) = containers.first().and_then(|c| container_state(c.1)) {
+        return state;
+    }
+
+    // not yet scheduled
+
+    let unschedulable = status.conditions.iter().flatten().any(|c| {
+        c.type_ == "PodScheduled"
+            && c.status == "False"
+            && c.reason.as_deref() == Some("Unschedulable")
+    });
+    if unschedulable {
+        return PodState::Unschedulable;
     }
 
     // eval status
@@ -98,6 +240,77 @@ pub fn make_state(status: &PodStatus) -> PodState {
     status.phase.as_deref().into()
 }
 
+/// The condition of a pod which could not be scheduled, if any.
+pub fn unscheduled(status: &PodStatus) -> Option<&PodCondition> {
+    status
+        .conditions
+        .iter()
+        .flatten()
+        .find(|c| c.type_ == "PodScheduled" && c.status == "False")
+}
+
+/// Split a message of the scheduler into sections, with their summary and individual reasons.
+///
+/// Messages look like: `0/3 nodes are available: 1 Insufficient cpu, 2 node(s) had untolerated
+/// taint {node-role.kubernetes.io/control-plane: }. preemption: 0/3 nodes are available: ...`
+pub fn explain_scheduling(message: &str) -> Vec<(&str, Vec<&str>)> {
+    split_top_level(message.trim().trim_end_matches('.'), ". ")
+        .into_iter()
+        .map(|section| {
+            // the reasons follow the last colon, outside of braces
+            let parts = split_top_level(section, ": ");
+            match parts.last() {
+                Some(reasons) if parts.len() > 1 => (
+                    &section[..section.len() - reasons.len() - 2],
+                    split_top_level(reasons, ", "),
+                ),
+                _ => (section, vec![]),
+            }
+        })
+        .collect()
+}
+
+/// Split a string, but not inside braces.
+fn split_top_level<'s>(s: &'s str, separator: &str) -> Vec<&'s str> {
+    let mut result = vec![];
+    let mut depth = 0usize;
+    let mut start = 0;
+
+    for (i, c) in s.char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' => depth = depth.saturating_sub(1),
+            _ if depth == 0 && i >= start && s[i..].starts_with(separator) => {
+                result.push(&s[start..i]);
+                start = i + separator.len();
+            }
+            _ => {}
+        }
+    }
+    result.push(&s[start..]);
+
+    result
+}
+
+/// The state of a container, if it is waiting or terminated.
+fn container_state(container: &ContainerStatus) -> Option<PodState> {
+    let state = container.state.as_ref()?;
+
+    if let Some(waiting) = &state.waiting {
+        return waiting.reason.as_ref().map(PodState::from);
+    }
+
+    let terminated = state.terminated.as_ref()?;
+    if let Some(reason) = &terminated.reason {
+        return Some(reason.into());
+    }
+
+    Some(match terminated.signal {
+        Some(signal) if signal != 0 => PodState::Signal(signal),
+        _ => PodState::ExitCode(terminated.exit_code),
+    })
+}
+
 pub fn make_ready(status: &PodStatus) -> Option<String> {
     if let Some(init_container_statuses) = &status.init_container_statuses {
         let total = init_container_statuses.len();
@@ -121,7 +334,7 @@ pub fn make_restarts(status: &PodStatus) -> Option<String> {
 
     // sort by latest change
 
-    containers.sort_unstable_by(|a, b| a.0 .0.cmp(&b.0 .0));
+    containers.sort_unstable_by_key(|a| a.0 .0);
 
     if let Some(c) = containers.first() {
         let cnt = c.1.restart_count;
@@ -189,3 +402,235 @@ impl LastChange for ContainerStatus {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use chrono::{Duration, SecondsFormat};
+    use serde_json::{json, Value};
+
+    fn ago(minutes: i64) -> String {
+        (Utc::now() - Duration::minutes(minutes)).to_rfc3339_opts(SecondsFormat::Secs, true)
+    }
+
+    fn container(name: &str, ready: bool, state: Value) -> Value {
+        json!({
+            "name": name,
+            "image": "image",
+            "imageID": "",
+            "ready": ready,
+            "restartCount": 0,
+            "state": state,
+        })
+    }
+
+    fn pod_status(value: Value) -> PodStatus {
+        serde_json::from_value(value).unwrap()
+    }
+
+    fn running() -> Value {
+        json!({ "running": { "startedAt": ago(10) } })
+    }
+
+    fn waiting(reason: &str) -> Value {
+        json!({ "waiting": { "reason": reason } })
+    }
+
+    fn terminated(reason: Option<&str>, exit_code: i32) -> Value {
+        json!({ "terminated": { "reason": reason, "exitCode": exit_code, "finishedAt": ago(5) } })
+    }
+
+    #[test]
+    fn state_running() {
+        let status = pod_status(json!({
+            "phase": "Running",
+            "containerStatuses": [container("app", true, running())],
+        }));
+        assert_eq!(make_state(&status), PodState::Running);
+    }
+
+    #[test]
+    fn state_waiting_reasons() {
+        for (reason, expected) in [
+            ("ContainerCreating", PodState::ContainerCreating),
+            ("CrashLoopBackOff", PodState::CrashLoopBackOff),
+            ("ImagePullBackOff", PodState::ImagePullBackOff),
+            ("ErrImagePull", PodState::ErrImagePull),
+            ("InvalidImageName", PodState::InvalidImageName),
+            (
+                "CreateContainerConfigError",
+                PodState::CreateContainerConfigError,
+            ),
+            ("SomethingNew", PodState::Other("SomethingNew".into())),
+        ] {
+            let status = pod_status(json!({
+                "phase": "Pending",
+                "containerStatuses": [container("app", false, waiting(reason))],
+            }));
+            assert_eq!(make_state(&status), expected, "{reason}");
+            assert_eq!(make_state(&status).to_string(), reason);
+        }
+    }
+
+    #[test]
+    fn state_terminated() {
+        let status = pod_status(json!({
+            "phase": "Running",
+            "containerStatuses": [container("app", false, terminated(Some("OOMKilled"), 137))],
+        }));
+        assert_eq!(make_state(&status), PodState::OOMKilled);
+
+        let status = pod_status(json!({
+            "phase": "Running",
+            "containerStatuses": [container("app", false, terminated(None, 3))],
+        }));
+        assert_eq!(make_state(&status), PodState::ExitCode(3));
+        assert_eq!(make_state(&status).to_string(), "ExitCode:3");
+    }
+
+    #[test]
+    fn state_completed() {
+        let status = pod_status(json!({
+            "phase": "Succeeded",
+            "containerStatuses": [container("app", false, terminated(Some("Completed"), 0))],
+        }));
+        assert_eq!(make_state(&status), PodState::Completed);
+        assert_eq!(make_state(&status).severity(), Severity::Ok);
+    }
+
+    #[test]
+    fn state_init() {
+        let status = pod_status(json!({
+            "phase": "Pending",
+            "initContainerStatuses": [
+                container("setup", true, terminated(Some("Completed"), 0)),
+                container("migrate", false, terminated(Some("Error"), 1)),
+            ],
+            "containerStatuses": [container("app", false, waiting("PodInitializing"))],
+        }));
+        let state = make_state(&status);
+        assert_eq!(state, PodState::Init(Box::new(PodState::Error)));
+        assert_eq!(state.to_string(), "Init:Error");
+        assert_eq!(state.severity(), Severity::Error);
+
+        // while init containers are running, the pod is initializing
+        let status = pod_status(json!({
+            "phase": "Pending",
+            "initContainerStatuses": [container("setup", false, running())],
+            "containerStatuses": [container("app", false, waiting("PodInitializing"))],
+        }));
+        assert_eq!(make_state(&status), PodState::PodInitializing);
+    }
+
+    #[test]
+    fn state_pod_reason() {
+        let status = pod_status(json!({
+            "phase": "Failed",
+            "reason": "Evicted",
+            "message": "The node was low on resource: memory.",
+        }));
+        assert_eq!(make_state(&status), PodState::Evicted);
+
+        let status = pod_status(json!({ "phase": "Unknown", "reason": "NodeLost" }));
+        assert_eq!(make_state(&status), PodState::NodeLost);
+    }
+
+    #[test]
+    fn state_unschedulable() {
+        let status = pod_status(json!({
+            "phase": "Pending",
+            "conditions": [{
+                "type": "PodScheduled",
+                "status": "False",
+                "reason": "Unschedulable",
+                "message": "0/3 nodes are available: 3 Insufficient cpu.",
+            }],
+        }));
+        assert_eq!(make_state(&status), PodState::Unschedulable);
+    }
+
+    #[test]
+    fn state_phase() {
+        assert_eq!(make_state(&pod_status(json!({}))), PodState::Unknown);
+        assert_eq!(
+            make_state(&pod_status(json!({ "phase": "Pending" }))),
+            PodState::Pending
+        );
+    }
+
+    #[test]
+    fn scheduling_message() {
+        let message = "0/3 nodes are available: 1 node(s) had untolerated taint \
+            {node-role.kubernetes.io/control-plane: }, 2 Insufficient cpu. preemption: 0/3 nodes \
+            are available: 3 No preemption victims found for incoming pod.";
+
+        assert_eq!(
+            explain_scheduling(message),
+            vec![
+                (
+                    "0/3 nodes are available",
+                    vec![
+                        "1 node(s) had untolerated taint {node-role.kubernetes.io/control-plane: }",
+                        "2 Insufficient cpu"
+                    ]
+                ),
+                (
+                    "preemption: 0/3 nodes are available",
+                    vec!["3 No preemption victims found for incoming pod"]
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn severity_order() {
+        assert!(PodState::CrashLoopBackOff.severity() > PodState::Error.severity());
+        assert!(PodState::Error.severity() > PodState::ImagePullBackOff.severity());
+        assert!(PodState::ImagePullBackOff.severity() > PodState::Pending.severity());
+        assert!(PodState::Pending.severity() > PodState::Running.severity());
+    }
+
+    #[test]
+    fn ready() {
+        assert_eq!(make_ready(&pod_status(json!({}))), None);
+
+        let status = pod_status(json!({
+            "containerStatuses": [
+                container("app", true, running()),
+                container("sidecar", false, running()),
+            ],
+        }));
+        assert_eq!(make_ready(&status).as_deref(), Some("1/2"));
+    }
+
+    #[test]
+    fn ready_init() {
+        let status = pod_status(json!({
+            "initContainerStatuses": [
+                container("setup", true, terminated(Some("Completed"), 0)),
+                container("migrate", false, running()),
+            ],
+            "containerStatuses": [container("app", false, waiting("PodInitializing"))],
+        }));
+        assert_eq!(make_ready(&status).as_deref(), Some("Init:1/2"));
+    }
+
+    #[test]
+    fn restarts() {
+        let status = pod_status(json!({
+            "containerStatuses": [container("app", true, running())],
+        }));
+        assert_eq!(make_restarts(&status), None);
+
+        let mut value = container("app", true, running());
+        value["restartCount"] = 3.into();
+        value["lastState"] = terminated(Some("Error"), 1);
+        let status = pod_status(json!({ "containerStatuses": [value] }));
+        assert_eq!(make_restarts(&status).as_deref(), Some("3 (5m ago)"));
+
+        let mut value = container("app", false, waiting("CrashLoopBackOff"));
+        value["restartCount"] = 1.into();
+        let status = pod_status(json!({ "containerStatuses": [value] }));
+        assert_eq!(make_restarts(&status).as_deref(), Some("1"));
+    }
+}
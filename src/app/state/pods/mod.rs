@@ -1,96 +1,58 @@
 mod data;
+mod view;
 
-use data::*;
+pub use data::*;
+pub use view::*;
 
 use crate::k8s::ago;
-use crate::{app::state::list::ListResource, client::Client, input::key::Key};
-use k8s_openapi::api::core::v1::Pod;
-use kube::{
-    api::{DeleteParams, Preconditions},
-    Api, Resource, ResourceExt,
+use crate::ui::theme::theme;
+use crate::{
+    app::state::{
+        columns::RowContent,
+        list::{ListResource, Outcome},
+    },
+    client::Client,
+    input::keymap::Action,
 };
-use ratatui::{layout::*, style::*, widgets::*};
-use std::{fmt::Debug, future::Future, hash::Hash, pin::Pin, sync::Arc};
+use k8s_openapi::api::core::v1::Pod;
+use kube::{Resource, ResourceExt};
+use ratatui::style::*;
+use std::{cmp::Reverse, convert::Infallible, future::Future, pin::Pin, sync::Arc};
 
 impl ListResource for Pod {
     type Resource = Self;
-    type Message = Msg;
-
-    fn render_table<'a>(items: &mut [Arc<Self::Resource>]) -> Table<'a>
-    where
-        <<Self as ListResource>::Resource as Resource>::DynamicType: Hash + Eq,
-    {
-        items.sort_unstable_by_key(|a| a.name_any());
+    type Message = Infallible;
 
-        let selected_style = Style::default().add_modifier(Modifier::REVERSED);
-        let normal_style = Style::default();
-        let header_cells = ["Name", "Ready", "State", "Restarts", "Age"]
-            .iter()
-            .map(|h| Cell::from(*h).style(Style::default().add_modifier(Modifier::BOLD)));
-        let header = Row::new(header_cells).style(normal_style).height(1);
+    const TITLE: &'static str = "Pods";
+    const COLUMNS: &'static [&'static str] = &["Name", "Ready", "State", "Restarts", "Age"];
+    const EVICTABLE: bool = true;
 
-        let rows: Vec<Row> = items.iter().map(|pod| make_row(pod)).collect();
+    fn deletes(action: Action) -> bool {
+        matches!(action, Action::Delete | Action::KillPod)
+    }
 
-        Table::new(
-            rows,
-            [
-                Constraint::Min(64),
-                Constraint::Min(10),
-                Constraint::Min(20),
-                Constraint::Min(15),
-                Constraint::Min(10),
-            ],
-        )
-        .header(header)
-        .block(Block::default().borders(Borders::ALL).title("Pods"))
-        .highlight_style(selected_style)
-        .highlight_symbol(">> ")
+    /// Sort by severity first, showing problems on top
+    fn sort(items: &mut [Arc<Self::Resource>]) {
+        items.sort_by_cached_key(|pod| (Reverse(pod_state(pod).severity()), pod.name_any()));
     }
 
-    fn on_key(items: &[Arc<Self::Resource>], state: &TableState, key: Key) -> Option<Self::Message>
-    where
-        <<Self as ListResource>::Resource as kube::Resource>::DynamicType: Hash + Eq,
-    {
-        match key {
-            Key::Char('k') => trigger_kill(items, state),
-            _ => None,
-        }
+    fn make_row(pod: &Self::Resource) -> RowContent {
+        make_row(pod)
     }
 
     fn process(
-        client: Arc<Client>,
+        _client: Arc<Client>,
         msg: Self::Message,
-    ) -> Pin<Box<dyn Future<Output = ()> + Send>> {
-        Box::pin(async {
-            match msg {
-                Msg::KillPod(pod) => execute_kill(client, &pod).await,
-            }
-        })
-    }
-}
-
-fn trigger_kill(pods: &[Arc<Pod>], state: &TableState) -> Option<Msg> {
-    let mut pods = pods.to_vec();
-    pods.sort_unstable_by_key(|a| a.name_any());
-
-    if let Some(pod) = state.selected().and_then(|i| pods.get(i)) {
-        Some(Msg::KillPod(pod.clone()))
-    } else {
-        None
+    ) -> Pin<Box<dyn Future<Output = Vec<Outcome>> + Send>> {
+        match msg {}
     }
 }
 
-fn make_row<'a>(pod: &Pod) -> Row<'a> {
-    let mut style = Style::default();
-
+fn make_row(pod: &Pod) -> RowContent {
     let name = pod.name_any();
     let ready = pod.status.as_ref().and_then(make_ready).unwrap_or_default();
 
-    let state = if pod.meta().deletion_timestamp.is_some() {
-        PodState::Terminating
-    } else {
-        pod.status.as_ref().map(make_state).unwrap_or_default()
-    };
+    let state = pod_state(pod);
     let restarts = pod
         .status
         .as_ref()
@@ -102,55 +64,31 @@ fn make_row<'a>(pod: &Pod) -> Row<'a> {
         .and_then(ago)
         .unwrap_or_default();
 
-    match &state {
-        PodState::Pending => {
-            style = style.bg(Color::Rgb(128, 0, 128));
-        }
-        PodState::Error => {
-            style = style.bg(Color::Rgb(128, 0, 0)).add_modifier(Modifier::BOLD);
-        }
-        PodState::CrashLoopBackOff => {
-            style = style.bg(Color::Rgb(128, 0, 0));
-        }
-        PodState::Terminating => {
-            style = style.bg(Color::Rgb(128, 128, 0));
-        }
-        _ => {}
-    }
+    let style = state_style(&state);
 
-    Row::new(vec![name, ready, state.to_string(), restarts, age]).style(style)
+    RowContent::new(vec![name, ready, state.to_string(), restarts, age]).style(style)
 }
 
-#[derive(Debug)]
-pub enum Msg {
-    KillPod(Arc<Pod>),
+/// The style of a pod state, based on its severity.
+pub fn state_style(state: &PodState) -> Style {
+    let theme = theme();
+    match state {
+        PodState::Terminating => theme.pod_terminating,
+        state => match state.severity() {
+            Severity::Ok => Style::default(),
+            Severity::Progressing => theme.pod_pending,
+            Severity::Warning => theme.pod_warning,
+            Severity::Error => theme.pod_error,
+            Severity::Critical => theme.pod_crash_loop,
+        },
+    }
 }
 
-async fn execute_kill(client: Arc<Client>, pod: &Pod) {
-    let result = client
-        .run(|context| async move {
-            if let Some(namespace) = pod.namespace() {
-                let pods: Api<Pod> = Api::namespaced(context.client, &namespace);
-
-                pods.delete(
-                    &pod.name_any(),
-                    &DeleteParams::default().preconditions(Preconditions {
-                        uid: pod.uid(),
-                        ..Default::default()
-                    }),
-                )
-                .await?;
-            }
-            Ok::<_, anyhow::Error>(())
-        })
-        .await;
-
-    match result {
-        Ok(_) => {
-            log::info!("Pod killed");
-        }
-        Err(err) => {
-            log::warn!("Failed to kill pod: {err}");
-        }
+/// The state of a pod, as shown in the list.
+pub fn pod_state(pod: &Pod) -> PodState {
+    if pod.meta().deletion_timestamp.is_some() {
+        PodState::Terminating
+    } else {
+        pod.status.as_ref().map(make_state).unwrap_or_default()
     }
 }
@@ -0,0 +1,189 @@
+use super::data::*;
+use crate::{
+    app::state::{
+        columns::ColumnConfig,
+        list::{Filter, ListWatcher, Object},
+        registry::Registry,
+    },
+    input::{keymap::Action, mouse::Mouse},
+    ui::{theme::theme, StateRenderer},
+};
+use k8s_openapi::api::core::v1::Pod;
+use ratatui::{layout::*, style::*, text::*, widgets::*};
+use std::sync::Arc;
+
+/// The list of pods, with an optional detail pane for the selected one.
+pub struct PodView {
+    list: ListWatcher<Pod>,
+    details: bool,
+}
+
+impl PodView {
+    pub fn new(registry: &Registry, columns: ColumnConfig) -> Self {
+        Self {
+            list: ListWatcher::new(registry, columns),
+            details: false,
+        }
+    }
+
+    pub fn render<SR: StateRenderer>(&self, mut r: SR) {
+        let selected = self.list.selected();
+        let status = selected.as_ref().and_then(|pod| status_line(pod));
+
+        let mut constraints = vec![match self.details {
+            true => Constraint::Percentage(40),
+            false => Constraint::Min(3),
+        }];
+        if status.is_some() {
+            constraints.push(Constraint::Length(1));
+        }
+        if self.details {
+            constraints.push(Constraint::Min(3));
+        }
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(constraints)
+            .split(r.rect());
+        let mut chunks = chunks.iter();
+
+        if let Some(rect) = chunks.next() {
+            self.list.render(r.child(*rect));
+        }
+        if let (Some(status), Some(rect)) = (status, chunks.next()) {
+            r.render_child(status, *rect);
+        }
+        if let (true, Some(rect)) = (self.details, chunks.next()) {
+            r.render_child(render_details(selected.as_deref()), *rect);
+        }
+    }
+
+    pub fn set_filter(&self, filter: Option<Filter<Pod>>) {
+        self.list.set_filter(filter);
+    }
+
+    pub fn filter(&self) -> Option<String> {
+        self.list.filter()
+    }
+
+    pub fn items(&self) -> Vec<Arc<Pod>> {
+        self.list.items()
+    }
+
+    pub fn list(&self) -> &ListWatcher<Pod> {
+        &self.list
+    }
+
+    pub fn selected_object(&self) -> Option<serde_json::Result<Object>> {
+        self.list.selected_object()
+    }
+
+    pub async fn on_action(&mut self, action: Action) {
+        match action {
+            Action::ToggleDetails => self.details = !self.details,
+            _ => self.list.on_action(action).await,
+        }
+    }
+
+    pub async fn on_mouse(&mut self, mouse: Mouse) {
+        self.list.on_mouse(mouse).await;
+    }
+}
+
+/// A single line, explaining why the pod can't be scheduled.
+fn status_line<'a>(pod: &Pod) -> Option<Paragraph<'a>> {
+    let condition = pod.status.as_ref().and_then(unscheduled)?;
+
+    let reason = condition.reason.as_deref().unwrap_or("Not scheduled");
+    let message = condition.message.as_deref().unwrap_or_default();
+
+    Some(Paragraph::new(format!(" {reason}: {message}")).style(theme().pod_warning))
+}
+
+fn render_details<'a>(pod: Option<&Pod>) -> Paragraph<'a> {
+    let block = Block::default().borders(Borders::ALL).title("Details");
+
+    let Some(pod) = pod else {
+        return Paragraph::new("").block(block);
+    };
+
+    let bold = Style::default().add_modifier(Modifier::BOLD);
+    let mut lines = vec![];
+
+    let node = pod
+        .spec
+        .as_ref()
+        .and_then(|spec| spec.node_name.clone())
+        .unwrap_or_else(|| "<none>".into());
+    lines.push(Line::from(vec![
+        Span::styled("Node: ", bold),
+        Span::from(node),
+    ]));
+    lines.push(Line::from(vec![
+        Span::styled("State: ", bold),
+        Span::from(super::pod_state(pod).to_string()),
+    ]));
+
+    let Some(status) = &pod.status else {
+        return Paragraph::new(lines).block(block);
+    };
+
+    if let Some(condition) = unscheduled(status) {
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled("Scheduling", bold)));
+        lines.push(Line::from(format!(
+            "  {}",
+            condition.reason.as_deref().unwrap_or("Not scheduled")
+        )));
+        for (summary, reasons) in
+            explain_scheduling(condition.message.as_deref().unwrap_or_default())
+        {
+            lines.push(Line::from(format!("  {summary}")));
+            lines.extend(
+                reasons
+                    .into_iter()
+                    .map(|r| Line::from(format!("    - {r}"))),
+            );
+        }
+    }
+
+    let containers: Vec<_> = all_containers(status)
+        .filter_map(|c| {
+            let state = c.state.as_ref()?;
+            let (reason, message) = match (&state.waiting, &state.terminated) {
+                (Some(waiting), _) => (waiting.reason.as_ref(), waiting.message.as_ref()),
+                (_, Some(terminated)) => (terminated.reason.as_ref(), terminated.message.as_ref()),
+                _ => return None,
+            };
+            Some((&c.name, reason, message))
+        })
+        .collect();
+    if !containers.is_empty() {
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled("Containers", bold)));
+        for (name, reason, message) in containers {
+            let mut line = format!("  {name}: {}", reason.map(String::as_str).unwrap_or("-"));
+            if let Some(message) = message {
+                line.push_str(&format!(" ({message})"));
+            }
+            lines.push(Line::from(line));
+        }
+    }
+
+    let conditions = status.conditions.iter().flatten().collect::<Vec<_>>();
+    if !conditions.is_empty() {
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled("Conditions", bold)));
+        for condition in conditions {
+            let mut line = format!("  {}: {}", condition.type_, condition.status);
+            if let Some(reason) = &condition.reason {
+                line.push_str(&format!(" ({reason})"));
+            }
+            lines.push(Line::from(line));
+        }
+    }
+
+    Paragraph::new(lines)
+        .block(block)
+        .wrap(Wrap { trim: false })
+}
@@ -0,0 +1,381 @@
+use crate::{client::Client, input::events::Redraw, k8s::Reflector};
+use anyhow::anyhow;
+use futures::StreamExt;
+use k8s_openapi::{serde::de::DeserializeOwned, NamespaceResourceScope};
+use kube::{
+    runtime::{reflector::Store, watcher},
+    ResourceExt,
+};
+use log::log_enabled;
+use std::{
+    any::{Any, TypeId},
+    collections::HashMap,
+    fmt::Debug,
+    hash::Hash,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+use tokio::{spawn, task::JoinHandle};
+
+/// Watchers are identified by their resource type and namespace
+type Key = (TypeId, Option<String>);
+
+/// The watchers of a session, shared by all views.
+///
+/// There is at most one watcher per resource type and namespace. Views hold on to the watchers
+/// they use, so switching back to a view doesn't need to list all objects again.
+#[derive(Clone)]
+pub struct Registry {
+    client: Client,
+    redraw: Redraw,
+    watchers: Arc<Mutex<HashMap<Key, Arc<dyn Watch>>>>,
+}
+
+/// The status of a watcher, for debugging.
+#[derive(Clone, Debug)]
+pub struct WatcherStatus {
+    pub kind: String,
+    pub namespace: Option<String>,
+    /// The number of views using the watcher
+    pub users: usize,
+    pub health: Health,
+    pub state: String,
+    /// Whether the task running the watch is still alive
+    pub running: bool,
+    pub events: usize,
+    pub reconnects: usize,
+    /// The time since the last event
+    pub last_event: Option<Duration>,
+}
+
+/// The connection state of a watcher.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Health {
+    /// Listing the objects for the first time
+    Loading,
+    Ok,
+    /// Reconnecting, showing the last known objects
+    Stale,
+    /// Reconnecting, without any objects to show
+    Error,
+}
+
+/// A watcher, independent of its resource type.
+trait Watch: Send + Sync {
+    fn status(&self) -> WatcherStatus;
+
+    fn as_any(self: Arc<Self>) -> Arc<dyn Any + Send + Sync>;
+}
+
+impl Registry {
+    pub fn new(client: Client) -> Self {
+        Self {
+            client,
+            redraw: Default::default(),
+            watchers: Default::default(),
+        }
+    }
+
+    pub fn client(&self) -> &Client {
+        &self.client
+    }
+
+    /// Requests redrawing the UI, whenever the content of a watcher changes
+    pub fn redraw(&self) -> &Redraw {
+        &self.redraw
+    }
+
+    /// Get the watcher of a resource type, starting it if necessary.
+    pub fn watch<K>(&self) -> Arc<Watcher<K>>
+    where
+        K: kube::Resource<Scope = NamespaceResourceScope>
+            + Clone
+            + Debug
+            + Send
+            + Sync
+            + DeserializeOwned
+            + 'static,
+        K::DynamicType: Hash + Eq + Clone + Default,
+    {
+        let mut watchers = self.watchers.lock().unwrap();
+        if let Some(watcher) = Self::find(&watchers, self.key::<K>()) {
+            return watcher;
+        }
+
+        let watcher = Arc::new(Watcher::new(self.client.clone(), self.redraw.clone()));
+        watchers.insert(self.key::<K>(), watcher.clone());
+        watcher
+    }
+
+    /// Get the watcher of a resource type, only if some view already started it.
+    pub fn peek<K>(&self) -> Option<Arc<Watcher<K>>>
+    where
+        K: kube::Resource + Send + Sync + 'static,
+        K::DynamicType: Hash + Eq,
+    {
+        Self::find(&self.watchers.lock().unwrap(), self.key::<K>())
+    }
+
+    fn key<K: 'static>(&self) -> Key {
+        (
+            TypeId::of::<K>(),
+            self.client.namespace().map(ToString::to_string),
+        )
+    }
+
+    fn find<K>(watchers: &HashMap<Key, Arc<dyn Watch>>, key: Key) -> Option<Arc<Watcher<K>>>
+    where
+        K: kube::Resource + Send + Sync + 'static,
+        K::DynamicType: Hash + Eq,
+    {
+        watchers
+            .get(&key)
+            .and_then(|w| w.clone().as_any().downcast::<Watcher<K>>().ok())
+    }
+
+    /// The status of all watchers, ordered by kind.
+    pub fn status(&self) -> Vec<WatcherStatus> {
+        let mut status = self
+            .watchers
+            .lock()
+            .unwrap()
+            .values()
+            .map(|watcher| WatcherStatus {
+                // one reference is held by the registry itself
+                users: Arc::strong_count(watcher) - 1,
+                ..watcher.status()
+            })
+            .collect::<Vec<_>>();
+        status.sort_unstable_by(|a, b| (&a.kind, &a.namespace).cmp(&(&b.kind, &b.namespace)));
+        status
+    }
+
+    /// Drop all watchers which are no longer used by any view.
+    pub fn evict_unused(&self) {
+        self.watchers
+            .lock()
+            .unwrap()
+            .retain(|_, watcher| Arc::strong_count(watcher) > 1);
+    }
+}
+
+/// The state of a watch
+pub enum State<K>
+where
+    K: kube::Resource + 'static,
+    K::DynamicType: Hash + Eq,
+{
+    Loading,
+    List(Store<K>),
+    Error(anyhow::Error),
+}
+
+#[derive(Clone, Debug, Default)]
+struct Stats {
+    events: usize,
+    reconnects: usize,
+    last_event: Option<Instant>,
+    /// The current error, while the content is stale
+    error: Option<String>,
+}
+
+/// A running watch of a resource type, keeping its objects in a store.
+///
+/// The watch is stopped when the watcher is dropped.
+pub struct Watcher<K>
+where
+    K: kube::Resource + 'static,
+    K::DynamicType: Hash + Eq,
+{
+    pub state: Arc<Mutex<State<K>>>,
+    stats: Arc<Mutex<Stats>>,
+    namespace: Option<String>,
+    runner: JoinHandle<()>,
+}
+
+impl<K> Drop for Watcher<K>
+where
+    K: kube::Resource + 'static,
+    K::DynamicType: Hash + Eq,
+{
+    fn drop(&mut self) {
+        self.runner.abort();
+    }
+}
+
+impl<K> Watcher<K>
+where
+    K: kube::Resource<Scope = NamespaceResourceScope>
+        + Clone
+        + Debug
+        + Send
+        + Sync
+        + DeserializeOwned
+        + 'static,
+    K::DynamicType: Hash + Eq + Clone + Default,
+{
+    fn new(client: Client, redraw: Redraw) -> Self {
+        let state = Arc::new(Mutex::new(State::Loading));
+        let stats = Arc::new(Mutex::new(Stats::default()));
+        let namespace = client.namespace().map(ToString::to_string);
+        let runner = spawn(run(client, redraw, state.clone(), stats.clone()));
+
+        Self {
+            state,
+            stats,
+            namespace,
+            runner,
+        }
+    }
+}
+
+impl<K> Watcher<K>
+where
+    K: kube::Resource + 'static,
+    K::DynamicType: Hash + Eq,
+{
+    /// All objects, once they are listed.
+    pub fn items(&self) -> Option<Vec<Arc<K>>>
+    where
+        K: Clone,
+        K::DynamicType: Clone,
+    {
+        match &*self.state.lock().unwrap() {
+            State::List(store) => Some(store.state()),
+            _ => None,
+        }
+    }
+
+    /// The error of the watch and the time since its last event, if the content is stale.
+    pub fn stale(&self) -> Option<(String, Duration)> {
+        let stats = self.stats.lock().unwrap();
+        let age = stats.last_event.map(|i| i.elapsed()).unwrap_or_default();
+        stats.error.as_ref().map(|err| (err.clone(), age))
+    }
+}
+
+impl<K> Watch for Watcher<K>
+where
+    K: kube::Resource + Clone + Send + Sync + 'static,
+    K::DynamicType: Hash + Eq + Clone + Default,
+{
+    fn status(&self) -> WatcherStatus {
+        let (health, state) = match &*self.state.lock().unwrap() {
+            State::Loading => (Health::Loading, "Loading".to_string()),
+            State::List(store) => match self.stale() {
+                Some((err, _)) => (
+                    Health::Stale,
+                    format!("Stale ({} objects): {err}", store.state().len()),
+                ),
+                None => (
+                    Health::Ok,
+                    format!("Watching ({} objects)", store.state().len()),
+                ),
+            },
+            State::Error(err) => (Health::Error, format!("Error: {err}")),
+        };
+        let stats = self.stats.lock().unwrap().clone();
+
+        WatcherStatus {
+            kind: K::kind(&Default::default()).to_string(),
+            namespace: self.namespace.clone(),
+            users: 0,
+            health,
+            state,
+            running: !self.runner.is_finished(),
+            events: stats.events,
+            reconnects: stats.reconnects,
+            last_event: stats.last_event.map(|i| i.elapsed()),
+        }
+    }
+
+    fn as_any(self: Arc<Self>) -> Arc<dyn Any + Send + Sync> {
+        self
+    }
+}
+
+/// The delay before the first reconnect, doubled for each failed attempt
+const INITIAL_BACKOFF: Duration = Duration::from_millis(500);
+/// The maximum delay between reconnects
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+async fn run<K>(
+    client: Client,
+    redraw: Redraw,
+    state: Arc<Mutex<State<K>>>,
+    stats: Arc<Mutex<Stats>>,
+) where
+    K: kube::Resource<Scope = NamespaceResourceScope>
+        + Clone
+        + Debug
+        + Send
+        + Sync
+        + DeserializeOwned
+        + 'static,
+    K::DynamicType: Hash + Eq + Clone + Default,
+{
+    let mut backoff = INITIAL_BACKOFF;
+
+    loop {
+        let err = match Reflector::new(&client).await {
+            Ok(mut r) => {
+                // creating the reflector doesn't talk to the API server yet. Keep showing the
+                // previous content (or error) until the new store has listed all objects.
+                loop {
+                    match r.stream.next().await {
+                        Some(Ok(evt)) => {
+                            if log_enabled!(log::Level::Info) {
+                                // only log the object references, as the payload might contain
+                                // sensitive information (like secrets)
+                                log::info!("Watch event: {}", describe(&evt));
+                            }
+                            if let watcher::Event::Restarted(_) = evt {
+                                *state.lock().unwrap() = State::List(r.reader.clone());
+                            }
+
+                            let mut stats = stats.lock().unwrap();
+                            stats.events += 1;
+                            stats.last_event = Some(Instant::now());
+                            stats.error = None;
+                            backoff = INITIAL_BACKOFF;
+                            redraw.request();
+                        }
+                        Some(Err(err)) => break anyhow!(err),
+                        None => break anyhow!("Stream closed"),
+                    }
+                }
+            }
+            Err(err) => err,
+        };
+
+        log::warn!("Watch error: {err}, reconnecting in {backoff:?}");
+        {
+            let mut state = state.lock().unwrap();
+            let mut stats = stats.lock().unwrap();
+            stats.reconnects += 1;
+            match *state {
+                // keep the last known content, marked as stale
+                State::List(_) => stats.error = Some(err.to_string()),
+                _ => *state = State::Error(err),
+            }
+        }
+        redraw.request();
+
+        tokio::time::sleep(backoff).await;
+        backoff = (backoff * 2).min(MAX_BACKOFF);
+    }
+}
+
+/// Describe a watch event, without leaking its content.
+fn describe<K: kube::Resource>(evt: &watcher::Event<K>) -> String {
+    let name = |k: &K| match k.meta().namespace.as_deref() {
+        Some(namespace) => format!("{namespace}/{}", k.name_any()),
+        None => k.name_any(),
+    };
+
+    match evt {
+        watcher::Event::Applied(k) => format!("Applied({})", name(k)),
+        watcher::Event::Deleted(k) => format!("Deleted({})", name(k)),
+        watcher::Event::Restarted(ks) => format!("Restarted({} items)", ks.len()),
+    }
+}
@@ -0,0 +1,145 @@
+use crate::app::state::{
+    overview::recent_warning, pod_state, registry::Registry, Deployments, Severity, View,
+};
+use anyhow::bail;
+use k8s_openapi::{
+    api::{
+        apps::v1::Deployment,
+        core::v1::{ConfigMap, Event, Pod, Secret},
+    },
+    serde::de::DeserializeOwned,
+};
+use ratatui::layout::Rect;
+use serde::Deserialize;
+use std::{fmt::Debug, hash::Hash, sync::Mutex};
+
+/// Configuration of the tab bar.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TabConfig {
+    /// Views shown as the first tabs, in this order. The others follow in their default order.
+    pub pinned: Vec<View>,
+    /// Views without a tab, they can still be shown using their key binding
+    pub hidden: Vec<View>,
+}
+
+impl TabConfig {
+    pub fn validate(&self) -> anyhow::Result<()> {
+        for (i, view) in self.pinned.iter().enumerate() {
+            if self.pinned[..i].contains(view) {
+                bail!("Tab {view:?} is pinned more than once");
+            }
+            if self.hidden.contains(view) {
+                bail!("Tab {view:?} is both pinned and hidden");
+            }
+        }
+        if View::ALL.iter().all(|view| self.hidden.contains(view)) {
+            bail!("All tabs are hidden");
+        }
+        Ok(())
+    }
+}
+
+/// The tabs of the views, in the configured order.
+pub struct Tabs {
+    views: Vec<View>,
+    /// Where the tabs were rendered last, to find the clicked one
+    areas: Mutex<Vec<(View, Rect)>>,
+}
+
+impl Tabs {
+    pub fn new(config: &TabConfig) -> Self {
+        let views = config
+            .pinned
+            .iter()
+            .copied()
+            .chain(View::ALL.into_iter().filter(|v| !config.pinned.contains(v)))
+            .filter(|v| !config.hidden.contains(v))
+            .collect();
+
+        Self {
+            views,
+            areas: Default::default(),
+        }
+    }
+
+    pub fn views(&self) -> &[View] {
+        &self.views
+    }
+
+    /// The view of a tab, by its position (starting at 0).
+    pub fn get(&self, index: usize) -> Option<View> {
+        self.views.get(index).copied()
+    }
+
+    /// The view of the previous tab, wrapping around.
+    pub fn prev(&self, view: View) -> View {
+        match self.views.iter().position(|v| *v == view) {
+            Some(idx) => self.views[(idx + self.views.len() - 1) % self.views.len()],
+            None => self.views[self.views.len() - 1],
+        }
+    }
+
+    /// The view of the next tab, wrapping around.
+    pub fn next(&self, view: View) -> View {
+        match self.views.iter().position(|v| *v == view) {
+            Some(idx) => self.views[(idx + 1) % self.views.len()],
+            None => self.views[0],
+        }
+    }
+
+    pub fn set_areas(&self, areas: Vec<(View, Rect)>) {
+        *self.areas.lock().unwrap() = areas;
+    }
+
+    /// The view of the tab at a position, if any.
+    pub fn at(&self, x: u16, y: u16) -> Option<View> {
+        self.areas
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|(_, area)| {
+                (area.left()..area.right()).contains(&x) && (area.top()..area.bottom()).contains(&y)
+            })
+            .map(|(view, _)| *view)
+    }
+}
+
+/// The number of objects of a view, and how many of them have a problem.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Summary {
+    pub count: usize,
+    pub problems: usize,
+}
+
+impl Summary {
+    /// Summarize the objects of a view, if they are already watched.
+    ///
+    /// Problems are failing pods, deployments which are not available and recent warnings.
+    pub fn of(view: View, registry: &Registry) -> Option<Self> {
+        match view {
+            View::Overview => None,
+            View::Pods => summarize::<Pod>(registry, |pod| {
+                pod_state(pod).severity() >= Severity::Warning
+            }),
+            View::Deployments => {
+                summarize::<Deployment>(registry, |d| !Deployments::is_available(d))
+            }
+            View::Events => summarize::<Event>(registry, recent_warning),
+            View::ConfigMaps => summarize::<ConfigMap>(registry, |_| false),
+            View::Secrets => summarize::<Secret>(registry, |_| false),
+        }
+    }
+}
+
+fn summarize<K>(registry: &Registry, problem: impl Fn(&K) -> bool) -> Option<Summary>
+where
+    K: kube::Resource + Clone + Debug + Send + Sync + DeserializeOwned + 'static,
+    K::DynamicType: Hash + Eq + Clone,
+{
+    let items = registry.peek::<K>()?.items()?;
+    Some(Summary {
+        count: items.len(),
+        problems: items.iter().filter(|item| problem(item)).count(),
+    })
+}
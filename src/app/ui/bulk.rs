@@ -0,0 +1,74 @@
+use crate::app::state::list::{Confirmation, Report};
+use crate::input::keymap::{Keymap, Scope};
+use crate::ui::{
+    popup::{draw_fitted_popup, fitted_height},
+    theme::theme,
+};
+use ratatui::{style::*, text::*, Frame};
+
+/// Draw the objects an action is about to affect, with its options, asking for confirmation.
+///
+/// The keys to confirm or cancel are shown in the status bar. The options come first, the list
+/// of objects is shortened to the space left.
+pub fn draw_confirmation(rect: &mut Frame, confirmation: &Confirmation, keymap: &Keymap) {
+    let mut lines: Vec<Line> = vec![];
+
+    if let Some(dialog) = &confirmation.delete {
+        for (action, name, value) in dialog.entries() {
+            let Some(key) = keymap.key(Scope::DeleteOptions, action) else {
+                continue;
+            };
+            lines.push(Line::from(vec![
+                Span::styled(
+                    format!(" {key} "),
+                    Style::default().add_modifier(Modifier::BOLD),
+                ),
+                Span::raw(format!("{name:<13}{value}")),
+            ]));
+        }
+        if let Some(hint) = dialog.hint() {
+            lines.push(Line::default());
+            lines.push(Line::from(format!(" {hint}")));
+        }
+        if let Some(warning) = dialog.warning() {
+            lines.push(Line::default());
+            lines.push(Line::styled(format!(" ⚠ {warning}"), theme().error));
+        }
+        lines.push(Line::default());
+    }
+
+    let size = rect.size();
+    let room = usize::from(
+        size.height
+            .saturating_sub(2)
+            .saturating_sub(fitted_height(size, &lines)),
+    );
+    let objects = &confirmation.objects;
+    let shown = match objects.len() > room {
+        true => room.saturating_sub(1).max(1),
+        false => objects.len(),
+    };
+    lines.extend(
+        objects[..shown]
+            .iter()
+            .map(|name| Line::from(format!(" {name}"))),
+    );
+    if shown < objects.len() {
+        lines.push(Line::from(format!(" … and {} more", objects.len() - shown)));
+    }
+
+    draw_fitted_popup(rect, &format!("{}?", confirmation.title), lines);
+}
+
+/// Draw the outcome of a bulk action, for each affected object.
+pub fn draw_report(rect: &mut Frame, report: &Report) {
+    let lines: Vec<Line> = report
+        .outcomes
+        .iter()
+        .map(|outcome| match &outcome.error {
+            None => Line::from(format!(" ✓ {}", outcome.name)),
+            Some(err) => Line::styled(format!(" ✗ {}: {err}", outcome.name), theme().error),
+        })
+        .collect();
+    draw_fitted_popup(rect, &report.title, lines);
+}
@@ -0,0 +1,65 @@
+use crate::app::state::{list::running_runners, registry::Registry};
+use crate::ui::theme::theme;
+use humantime::format_duration;
+use ratatui::{layout::*, widgets::*};
+use std::time::Duration;
+
+/// A table of all live watchers, to check that none are leaking.
+pub fn draw_debug<'a>(registry: &Registry) -> Table<'a> {
+    let theme = theme();
+
+    let rows = registry
+        .status()
+        .into_iter()
+        .map(|status| {
+            let last_event = status
+                .last_event
+                .map(|d| format!("{} ago", format_duration(Duration::from_secs(d.as_secs()))))
+                .unwrap_or_else(|| "-".into());
+            let state = match status.running {
+                true => status.state,
+                false => format!("Stopped ({})", status.state),
+            };
+
+            Row::new(vec![
+                status.kind,
+                status.namespace.unwrap_or_else(|| "<current>".into()),
+                status.users.to_string(),
+                state,
+                status.events.to_string(),
+                status.reconnects.to_string(),
+                last_event,
+            ])
+        })
+        .collect::<Vec<_>>();
+
+    Table::new(
+        rows,
+        [
+            Constraint::Length(12),
+            Constraint::Length(12),
+            Constraint::Length(5),
+            Constraint::Min(20),
+            Constraint::Length(7),
+            Constraint::Length(10),
+            Constraint::Length(12),
+        ],
+    )
+    .header(
+        Row::new([
+            "Kind",
+            "Namespace",
+            "Views",
+            "State",
+            "Events",
+            "Reconnects",
+            "Last event",
+        ])
+        .style(theme.header),
+    )
+    .block(
+        Block::default()
+            .title(format!("Watchers ({} action runners)", running_runners()))
+            .borders(Borders::ALL),
+    )
+}
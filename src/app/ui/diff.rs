@@ -0,0 +1,31 @@
+use crate::app::{
+    diff::Diff,
+    ui::{status::hint_line, theme::theme},
+};
+use ratatui::{layout::*, text::*, widgets::*, Frame};
+use similar::ChangeTag;
+
+pub fn draw_diff(rect: &mut Frame, diff: &Diff, hints: Vec<(String, &'static str)>) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(3), Constraint::Length(1)])
+        .split(rect.size());
+
+    let theme = theme();
+    let lines: Vec<Line> = diff
+        .lines()
+        .iter()
+        .map(|(tag, line)| match tag {
+            ChangeTag::Delete => Line::styled(format!("-{line}"), theme.diff_delete),
+            ChangeTag::Insert => Line::styled(format!("+{line}"), theme.diff_insert),
+            ChangeTag::Equal => Line::from(format!(" {line}")),
+        })
+        .collect();
+
+    let text = Paragraph::new(lines)
+        .scroll((diff.offset().try_into().unwrap_or(u16::MAX), 0))
+        .block(Block::default().title(diff.title()).borders(Borders::ALL));
+    rect.render_widget(text, chunks[0]);
+
+    rect.render_widget(Paragraph::new(hint_line(hints)), chunks[1]);
+}
@@ -1,9 +1,12 @@
+use crate::input::keymap::Keymap;
 use ratatui::{style::*, text::*, widgets::*, Frame};
 
-pub fn draw_help(rect: &mut Frame) {
+pub fn draw_help(rect: &mut Frame, keymap: &Keymap) {
+    let bold = Style::default().add_modifier(Modifier::BOLD);
+
     let mut text = Text::from("\n");
     text.extend(Text::from(Line::from(vec![
-        Span::styled(" Podium", Style::default().add_modifier(Modifier::BOLD)),
+        Span::styled(" Podium", bold),
         Span::from(" - "),
         Span::styled(
             "watch your pods",
@@ -11,31 +14,26 @@ pub fn draw_help(rect: &mut Frame) {
         ),
     ])));
 
-    text.extend(Text::from(
-        r#"
- Keys:
-   <Esc>   Exit the current view (or the application)
-   q, <Ctrl> + c   Exit the application
-
-   h   View this help   
-   l   Toggle log view
-   left, right
-       Cycle through views
-
-   d   View deployments
-   p   View pods
+    for scope in keymap.scopes() {
+        text.extend([
+            Line::from(""),
+            Line::from(Span::styled(format!(" ## {}", scope.title()), bold)),
+            Line::from(""),
+        ]);
 
-   ## Pods
-   
-   k   Kill selected pod
-   
-   ## Deployments
-   
-   r     Restart selected deployment
-   +, -  Scale up or down
+        for (action, keys) in keymap.bindings(scope) {
+            let keys = keys
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(", ");
+            text.extend([Line::from(vec![
+                Span::from(format!("   {keys:<24} ")),
+                Span::from(action.description()),
+            ])]);
+        }
+    }
 
-"#,
-    ));
     let help = Paragraph::new(text).block(Block::default().title("Help").borders(Borders::ALL));
     rect.render_widget(help, rect.size());
 }
@@ -0,0 +1,85 @@
+use crate::app::labels::{Input, LabelEditor, Section};
+use crate::ui::{popup::centered_rect, status::hint_line, theme::theme};
+use ratatui::{layout::*, style::*, text::*, widgets::*, Frame};
+
+/// Draw the labels and annotations of an object, with their pending changes.
+pub fn draw_labels(rect: &mut Frame, editor: &LabelEditor, hints: Vec<(String, &'static str)>) {
+    let area = centered_rect(80, 80, rect.size());
+    let block = Block::default()
+        .title(format!("Labels and annotations of {}", editor.name()))
+        .borders(Borders::ALL);
+    let inner = block.inner(area);
+    rect.render_widget(Clear, area);
+    rect.render_widget(block, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(1), Constraint::Length(2)])
+        .split(inner);
+
+    let width = editor
+        .entries()
+        .iter()
+        .map(|e| e.key.chars().count())
+        .max()
+        .unwrap_or_default();
+
+    let mut lines = vec![];
+    let mut selected = 0;
+    for section in [Section::Labels, Section::Annotations] {
+        lines.push(Line::styled(section.title(), theme().header));
+        let entries = editor
+            .entries()
+            .iter()
+            .enumerate()
+            .filter(|(_, e)| e.section == section);
+        for (idx, entry) in entries {
+            let marker = match (&entry.original, entry.removed) {
+                (_, true) => "-",
+                (None, _) => "+",
+                (Some(_), _) if entry.is_changed() => "~",
+                _ => " ",
+            };
+            let value = match entry.sensitive {
+                true => "(hidden)",
+                false => entry.value.as_str(),
+            };
+
+            let mut style = Style::default();
+            if entry.removed {
+                style = style.add_modifier(Modifier::CROSSED_OUT);
+            } else if entry.is_changed() {
+                style = style.patch(theme().marked);
+            }
+            if idx == editor.selected() {
+                style = style.patch(theme().selected);
+                selected = lines.len();
+            }
+            lines.push(Line::styled(
+                format!(" {marker} {:width$}  {value}", entry.key),
+                style,
+            ));
+        }
+    }
+
+    // keep the selected entry in view
+    let height = usize::from(chunks[0].height);
+    let offset = (selected + 1).saturating_sub(height);
+    let list = Paragraph::new(lines).scroll((offset.try_into().unwrap_or(u16::MAX), 0));
+    rect.render_widget(list, chunks[0]);
+
+    let status = match (editor.input(), editor.error()) {
+        (Some(Input::Add(section, text)), _) => {
+            let name = match section {
+                Section::Labels => "label",
+                Section::Annotations => "annotation",
+            };
+            Line::from(format!(" New {name} (key=value): {text}"))
+        }
+        (Some(Input::Change(text)), _) => Line::from(format!(" Value: {text}")),
+        (None, _) if editor.is_applying() => Line::from(" Applying changes…"),
+        (None, Some(error)) => Line::styled(format!(" {error}"), theme().error),
+        (None, None) => Line::default(),
+    };
+    rect.render_widget(Paragraph::new(vec![status, hint_line(hints)]), chunks[1]);
+}
@@ -0,0 +1,108 @@
+use crate::app::{
+    manifest::{Format, Manifest, Search},
+    ui::{status::hint_line, theme::theme},
+};
+use ratatui::{layout::*, style::*, text::*, widgets::*, Frame};
+
+pub fn draw_manifest(rect: &mut Frame, manifest: &Manifest, hints: Vec<(String, &'static str)>) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(3), Constraint::Length(1)])
+        .split(rect.size());
+
+    let term = match manifest.search() {
+        Search::Active(term) => Some(term.as_str()),
+        _ => None,
+    };
+
+    let lines: Vec<Line> = manifest
+        .lines()
+        .iter()
+        .map(|line| {
+            let mut line = highlight(line, manifest.format());
+            if term.is_some_and(|term| !term.is_empty() && line_contains(&line, term)) {
+                line.patch_style(theme().search_match);
+            }
+            line
+        })
+        .collect();
+
+    let text = Paragraph::new(lines)
+        .scroll((manifest.offset().try_into().unwrap_or(u16::MAX), 0))
+        .block(
+            Block::default()
+                .title(format!("Manifest ({})", manifest.options()))
+                .borders(Borders::ALL),
+        );
+    rect.render_widget(text, chunks[0]);
+
+    let status = match manifest.search() {
+        Search::Input(term) => Line::from(format!("/{term}")),
+        _ => hint_line(hints),
+    };
+    rect.render_widget(Paragraph::new(status), chunks[1]);
+}
+
+fn line_contains(line: &Line, term: &str) -> bool {
+    let text: String = line.spans.iter().map(|s| s.content.as_ref()).collect();
+    text.contains(term)
+}
+
+/// Apply some simple syntax highlighting to a line of YAML or JSON.
+fn highlight(line: &str, format: Format) -> Line<'static> {
+    let key_style = theme().manifest_key;
+
+    let trimmed = line.trim_start();
+    let (indent, rest) = line.split_at(line.len() - trimmed.len());
+
+    let mut spans = vec![Span::from(indent.to_string())];
+
+    let rest = match format {
+        Format::Yaml => {
+            let rest = match rest.strip_prefix("- ") {
+                Some(rest) => {
+                    spans.push(Span::from("- "));
+                    rest
+                }
+                None => rest,
+            };
+            if let Some((key, value)) = rest.split_once(": ") {
+                spans.push(Span::styled(key.to_string(), key_style));
+                spans.push(Span::from(": "));
+                value
+            } else if let Some(key) = rest.strip_suffix(':') {
+                spans.push(Span::styled(key.to_string(), key_style));
+                spans.push(Span::from(":"));
+                ""
+            } else {
+                rest
+            }
+        }
+        Format::Json => match rest.split_once("\": ") {
+            Some((key, value)) if key.starts_with('"') => {
+                spans.push(Span::styled(format!("{key}\""), key_style));
+                spans.push(Span::from(": "));
+                value
+            }
+            _ => rest,
+        },
+    };
+
+    if !rest.is_empty() {
+        spans.push(Span::styled(rest.to_string(), value_style(rest)));
+    }
+
+    Line::from(spans)
+}
+
+fn value_style(value: &str) -> Style {
+    let theme = theme();
+    let value = value.trim_end_matches(',');
+    match value {
+        "true" | "false" => theme.manifest_literal,
+        "null" | "~" => theme.manifest_null,
+        "{" | "}" | "[" | "]" | "{}" | "[]" => Style::default(),
+        _ if value.parse::<f64>().is_ok() => theme.manifest_literal,
+        _ => theme.manifest_string,
+    }
+}
@@ -1,11 +1,34 @@
+pub mod bulk;
+pub mod debug;
+pub mod diff;
 pub mod help;
+pub mod labels;
+pub mod manifest;
+pub mod popup;
 pub mod state;
-
-use crate::{ui::help::draw_help, App, Args};
+pub mod status;
+pub mod tabs;
+pub mod theme;
+
+use crate::{
+    ui::{
+        bulk::{draw_confirmation, draw_report},
+        debug::draw_debug,
+        diff::draw_diff,
+        help::draw_help,
+        labels::draw_labels,
+        manifest::draw_manifest,
+        popup::draw_popup,
+        status::{draw_status, hint_line},
+        tabs::draw_tabs,
+        theme::theme,
+    },
+    App,
+};
 use ratatui::{
     layout::{Alignment, Constraint, Direction, Layout, Rect},
-    style::{Color, Style},
-    widgets::{Block, BorderType, Borders, Paragraph, StatefulWidget, Widget},
+    text::{Line, Text},
+    widgets::{Block, Borders, Paragraph, StatefulWidget, Widget, Wrap},
     Frame,
 };
 use tui_logger::{TuiLoggerLevelOutput, TuiLoggerWidget};
@@ -23,6 +46,34 @@ pub trait StateRenderer {
     fn render_stateful<W: StatefulWidget>(&mut self, w: W, state: &mut W::State) {
         self.render_child_stateful(w, state, self.rect());
     }
+
+    /// Create a renderer for a sub-area of this renderer.
+    fn child(&mut self, rect: Rect) -> ChildRenderer<'_, Self>
+    where
+        Self: Sized,
+    {
+        ChildRenderer { inner: self, rect }
+    }
+}
+
+pub struct ChildRenderer<'r, R: StateRenderer> {
+    inner: &'r mut R,
+    rect: Rect,
+}
+
+impl<R: StateRenderer> StateRenderer for ChildRenderer<'_, R> {
+    #[inline]
+    fn rect(&self) -> Rect {
+        self.rect
+    }
+
+    fn render_child<W: Widget>(&mut self, w: W, rect: Rect) {
+        self.inner.render_child(w, rect);
+    }
+
+    fn render_child_stateful<W: StatefulWidget>(&mut self, w: W, state: &mut W::State, rect: Rect) {
+        self.inner.render_child_stateful(w, state, rect);
+    }
 }
 
 impl<'c, 'f> StateRenderer for RenderContext<'c, 'f> {
@@ -45,24 +96,53 @@ struct RenderContext<'c, 'f> {
     rect: Rect,
 }
 
+/// The minimum size of the terminal to show the UI in
+const MIN_WIDTH: u16 = 40;
+const MIN_HEIGHT: u16 = 8;
+
 pub fn draw(rect: &mut Frame, app: &App) {
-    if app.global.help {
-        draw_help(rect)
+    let size = rect.size();
+    if size.width < MIN_WIDTH || size.height < MIN_HEIGHT {
+        draw_too_small(rect);
+    } else if app.global.help {
+        draw_help(rect, app.keymap())
+    } else if let Some(manifest) = &app.global.manifest {
+        draw_manifest(rect, manifest, app.hints())
+    } else if let Some(diff) = &app.global.diff {
+        draw_diff(rect, diff, app.hints())
     } else {
-        draw_default(rect, app)
+        draw_default(rect, app);
+
+        if let Some(failure) = &app.global.edit_failure {
+            let mut text = Text::from(failure.error.clone());
+            text.extend([Line::default(), hint_line(app.hints())]);
+            draw_popup(rect, "Failed to apply changes", text);
+        } else if let Some(editor) = &app.global.labels {
+            draw_labels(rect, editor, app.hints());
+        } else if let Some(list) = app.state().list() {
+            if let Some(confirmation) = list.confirmation() {
+                draw_confirmation(rect, &confirmation, app.keymap());
+            } else if let Some(report) = list.report() {
+                draw_report(rect, &report);
+            }
+        }
     }
 }
 
 pub fn draw_default(rect: &mut Frame, app: &App) {
     let size = rect.size();
-    // TODO check size
 
     let logs = app.global().logs;
-    let mut constraints = vec![Constraint::Length(3), Constraint::Percentage(60)];
+    let debug = app.global().debug;
+    let mut constraints = vec![Constraint::Length(1), Constraint::Min(3)];
 
     if logs {
         constraints.push(Constraint::Percentage(40));
     }
+    if debug {
+        constraints.push(Constraint::Length(8));
+    }
+    constraints.push(Constraint::Length(2));
 
     // Vertical layout
     let chunks = Layout::default()
@@ -70,9 +150,8 @@ pub fn draw_default(rect: &mut Frame, app: &App) {
         .constraints(constraints)
         .split(size);
 
-    // Title block
-    let title = draw_title(&app.args);
-    rect.render_widget(title, chunks[0]);
+    // Header, with the tabs of all views
+    draw_tabs(rect, chunks[0], app);
 
     // Main
     app.state().render(RenderContext {
@@ -85,37 +164,48 @@ pub fn draw_default(rect: &mut Frame, app: &App) {
         let logs = draw_logs();
         rect.render_widget(logs, chunks[2]);
     }
+
+    // Watchers
+    if debug {
+        let debug = draw_debug(app.registry());
+        rect.render_widget(debug, chunks[chunks.len() - 2]);
+    }
+
+    // Status bar
+    let status = draw_status(app);
+    rect.render_widget(status, chunks[chunks.len() - 1]);
 }
 
-fn draw_title<'a>(args: &Args) -> Paragraph<'a> {
-    Paragraph::new(format!(
-        "Podium ({})",
-        args.namespace.as_deref().unwrap_or("<current>")
+fn draw_too_small(rect: &mut Frame) {
+    let size = rect.size();
+    let message = Paragraph::new(format!(
+        "Terminal too small ({}x{}), needs at least {MIN_WIDTH}x{MIN_HEIGHT}",
+        size.width, size.height
     ))
-    .style(Style::default().fg(Color::White))
+    .style(theme().error)
     .alignment(Alignment::Center)
-    .block(
-        Block::default()
-            .borders(Borders::ALL)
-            .style(Style::default().fg(Color::White))
-            .border_type(BorderType::Plain),
-    )
+    .wrap(Wrap { trim: true });
+
+    // vertically centered, if it fits into a single line
+    let y = size.height.saturating_sub(1) / 2;
+    rect.render_widget(message, Rect::new(0, y, size.width, size.height - y));
 }
 
 fn draw_logs<'a>() -> TuiLoggerWidget<'a> {
+    let theme = theme();
     TuiLoggerWidget::default()
         .output_timestamp(Some("%H:%M:%S%.3f".into()))
         .output_level(Some(TuiLoggerLevelOutput::Abbreviated))
-        .style_error(Style::default().fg(Color::Red))
-        .style_debug(Style::default().fg(Color::Green))
-        .style_warn(Style::default().fg(Color::Yellow))
-        .style_trace(Style::default().fg(Color::Gray))
-        .style_info(Style::default().fg(Color::Blue))
+        .style_error(theme.log_error)
+        .style_debug(theme.log_debug)
+        .style_warn(theme.log_warn)
+        .style_trace(theme.log_trace)
+        .style_info(theme.log_info)
         .block(
             Block::default()
                 .title("Logs")
-                .border_style(Style::default().fg(Color::White).bg(Color::Black))
+                .border_style(theme.logs)
                 .borders(Borders::ALL),
         )
-        .style(Style::default().fg(Color::White).bg(Color::Black))
+        .style(theme.logs)
 }
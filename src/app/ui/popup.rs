@@ -0,0 +1,60 @@
+use ratatui::{layout::*, text::*, widgets::*, Frame};
+
+/// Draw a popup, centered on top of the current content.
+pub fn draw_popup<'a>(rect: &mut Frame, title: &str, text: impl Into<Text<'a>>) {
+    let area = centered_rect(60, 40, rect.size());
+    let popup = Paragraph::new(text)
+        .wrap(Wrap { trim: false })
+        .block(Block::default().title(title).borders(Borders::ALL));
+
+    rect.render_widget(Clear, area);
+    rect.render_widget(popup, area);
+}
+
+/// Draw a popup as high as its (wrapped) lines, centered on top of the current content.
+pub fn draw_fitted_popup(rect: &mut Frame, title: &str, lines: Vec<Line>) {
+    let size = rect.size();
+    let mut area = centered_rect(60, 100, size);
+    let height = fitted_height(size, &lines)
+        .saturating_add(2)
+        .min(size.height);
+    area.y = (size.height - height) / 2;
+    area.height = height;
+
+    let popup = Paragraph::new(lines)
+        .wrap(Wrap { trim: false })
+        .block(Block::default().title(title).borders(Borders::ALL));
+
+    rect.render_widget(Clear, area);
+    rect.render_widget(popup, area);
+}
+
+/// The height of lines, when wrapped in a fitted popup.
+pub fn fitted_height(size: Rect, lines: &[Line]) -> u16 {
+    let inner = centered_rect(60, 100, size).width.saturating_sub(2).max(1);
+    lines
+        .iter()
+        .map(|line| (line.width() as u16).max(1).div_ceil(inner))
+        .sum()
+}
+
+/// Create a rectangle, centered in the provided area, using a percentage of its size.
+pub fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
+    let vertical = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Percentage((100 - percent_y) / 2),
+            Constraint::Percentage(percent_y),
+            Constraint::Percentage((100 - percent_y) / 2),
+        ])
+        .split(r);
+
+    Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage((100 - percent_x) / 2),
+            Constraint::Percentage(percent_x),
+            Constraint::Percentage((100 - percent_x) / 2),
+        ])
+        .split(vertical[1])[1]
+}
@@ -40,11 +40,9 @@ impl Paging for TableState {
                 if i == 0 {
                     // first cone, continue with last
                     total - 1
-                } else if i < increment {
-                    // close to top, go with first
-                    0
                 } else {
-                    i - increment
+                    // close to top, go with first
+                    i.saturating_sub(increment)
                 }
             }
             None => 0,
@@ -0,0 +1,78 @@
+use crate::{
+    app::state::{registry::Health, tabs::Summary},
+    ui::theme::theme,
+    App,
+};
+use ratatui::{style::*, text::*, widgets::*};
+
+/// The status bar: where the client is connected to, the state of the watches and key hints.
+pub fn draw_status<'a>(app: &App) -> Paragraph<'a> {
+    let theme = theme();
+    let cluster = app.cluster();
+
+    let mut sections: Vec<Vec<Span>> = vec![];
+    if let Some(context) = cluster.context {
+        sections.push(vec![Span::raw(format!("ctx: {context}"))]);
+    }
+    if let Some(server) = cluster.server {
+        sections.push(vec![Span::raw(server)]);
+    }
+    sections.push(vec![Span::raw(format!(
+        "ns: {}",
+        cluster.namespace.as_deref().unwrap_or("<current>")
+    ))]);
+    if let Some(version) = cluster.version {
+        sections.push(vec![Span::raw(version)]);
+    }
+
+    // only the watches used by any view
+    let mut watches = vec![Span::raw("watches:")];
+    for status in app.registry().status() {
+        if status.users == 0 {
+            continue;
+        }
+        let (symbol, style) = match status.health {
+            Health::Loading => ("…", Style::default()),
+            Health::Ok => ("✓", Style::default()),
+            Health::Stale | Health::Error => ("✗", theme.error),
+        };
+        watches.push(Span::raw(format!(" {} ", status.kind)));
+        watches.push(Span::styled(symbol, style));
+    }
+    if watches.len() > 1 {
+        sections.push(watches);
+    }
+
+    if let (Some(view), Some(count)) = (app.state().view(), app.state().count()) {
+        let total = Summary::of(view, app.registry()).map(|summary| summary.count);
+        let items = match total.unwrap_or(count) {
+            1 => "item",
+            _ => "items",
+        };
+        sections.push(vec![Span::raw(match total {
+            Some(total) if total != count => format!("{count} of {total} {items}"),
+            _ => format!("{count} {items}"),
+        })]);
+    }
+
+    let mut info = vec![Span::raw(" ")];
+    for (i, section) in sections.into_iter().enumerate() {
+        if i > 0 {
+            info.push(Span::raw(" │ "));
+        }
+        info.extend(section);
+    }
+
+    Paragraph::new(vec![Line::from(info), hint_line(app.hints())]).style(theme.title)
+}
+
+/// A line of key hints, the key in bold followed by its label.
+pub fn hint_line<'a>(hints: Vec<(String, &'static str)>) -> Line<'a> {
+    let bold = Style::default().add_modifier(Modifier::BOLD);
+    let mut spans = vec![Span::raw(" ")];
+    for (key, hint) in hints {
+        spans.push(Span::styled(key, bold));
+        spans.push(Span::raw(format!(" {hint}  ")));
+    }
+    Line::from(spans)
+}
@@ -0,0 +1,43 @@
+use crate::{app::state::tabs::Summary, ui::theme::theme, App};
+use ratatui::{layout::Rect, style::Style, text::*, widgets::Paragraph, Frame};
+
+/// The tabs of all views, with the number of objects and problems of each one.
+pub fn draw_tabs(frame: &mut Frame, area: Rect, app: &App) {
+    let theme = theme();
+    let current = app.state().view();
+
+    let mut spans = vec![];
+    let mut areas = vec![];
+    let mut x = area.x;
+
+    for (i, view) in app.tabs().views().iter().enumerate() {
+        let style = match current == Some(*view) {
+            true => theme.active_tab,
+            false => Style::default(),
+        };
+        let title = match i {
+            // only the first tabs have a shortcut
+            0..=8 => format!(" {} {} ", i + 1, view.title()),
+            _ => format!(" {} ", view.title()),
+        };
+
+        let mut tab = vec![Span::styled(title, style)];
+        if let Some(summary) = Summary::of(*view, app.registry()) {
+            tab.push(Span::styled(format!("{} ", summary.count), style));
+            if summary.problems > 0 {
+                tab.push(Span::styled(format!("{}! ", summary.problems), theme.error));
+            }
+        }
+
+        let width = tab.iter().map(Span::width).sum::<usize>() as u16;
+        if x < area.right() {
+            let width = width.min(area.right() - x);
+            areas.push((*view, Rect::new(x, area.y, width, 1)));
+        }
+        x = x.saturating_add(width);
+        spans.extend(tab);
+    }
+
+    app.tabs().set_areas(areas);
+    frame.render_widget(Paragraph::new(Line::from(spans)).style(theme.title), area);
+}
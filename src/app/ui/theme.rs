@@ -0,0 +1,315 @@
+use anyhow::{anyhow, bail};
+use ratatui::style::{Color, Modifier, Style};
+use serde::{Deserialize, Deserializer};
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+static THEME: OnceLock<Theme> = OnceLock::new();
+
+/// The active theme.
+///
+/// Falls back to the default theme, if none was set.
+pub fn theme() -> &'static Theme {
+    THEME.get_or_init(Theme::default)
+}
+
+/// The name of the theme to use, if none was configured.
+///
+/// Honors the `NO_COLOR` environment variable.
+pub fn default_theme_name() -> &'static str {
+    match std::env::var_os("NO_COLOR") {
+        Some(value) if !value.is_empty() => "none",
+        _ => "default",
+    }
+}
+
+/// Set the active theme, can only be done once.
+pub fn set_theme(theme: Theme) {
+    if THEME.set(theme).is_err() {
+        log::warn!("Theme already set");
+    }
+}
+
+macro_rules! theme {
+    ($($(#[$meta:meta])* $name:ident),* $(,)?) => {
+        /// Styles used by the user interface.
+        #[derive(Clone, Debug)]
+        pub struct Theme {
+            $($(#[$meta])* pub $name: Style,)*
+        }
+
+        /// A custom theme, overriding styles of a base theme.
+        #[derive(Clone, Debug, Default, Deserialize)]
+        #[serde(default, deny_unknown_fields)]
+        pub struct ThemeConfig {
+            /// The theme to start with
+            pub base: Option<String>,
+            $($name: Option<StyleConfig>,)*
+        }
+
+        impl ThemeConfig {
+            fn apply(&self, theme: &mut Theme) {
+                $(
+                    if let Some(style) = &self.$name {
+                        theme.$name = style.into();
+                    }
+                )*
+            }
+        }
+    };
+}
+
+theme!(
+    /// Table headers
+    header,
+    /// The selected row
+    selected,
+    /// Rows marked for a bulk action
+    marked,
+    /// The title bar
+    title,
+    /// The tab of the current view
+    active_tab,
+    /// Error messages
+    error,
+    pod_pending,
+    pod_warning,
+    pod_error,
+    pod_crash_loop,
+    pod_terminating,
+    /// Deployments without any ready replicas
+    deployment_unavailable,
+    /// Deployments with some ready replicas
+    deployment_degraded,
+    logs,
+    log_error,
+    log_warn,
+    log_info,
+    log_debug,
+    log_trace,
+    manifest_key,
+    manifest_string,
+    manifest_literal,
+    manifest_null,
+    search_match,
+    diff_insert,
+    diff_delete,
+);
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::dark()
+    }
+}
+
+impl Theme {
+    /// Names of the built-in themes
+    pub const BUILTIN: [&'static str; 4] = ["default", "16", "light", "none"];
+
+    /// Get a built-in theme by name.
+    pub fn builtin(name: &str) -> Option<Self> {
+        Some(match name {
+            "default" => Self::dark(),
+            "16" => Self::basic(),
+            "light" => Self::light(),
+            "none" => Self::none(),
+            _ => return None,
+        })
+    }
+
+    /// Resolve a theme by name, either custom or built-in.
+    pub fn resolve(name: &str, custom: &HashMap<String, ThemeConfig>) -> anyhow::Result<Self> {
+        Self::resolve_with(name, custom, &mut vec![])
+    }
+
+    fn resolve_with<'a>(
+        name: &'a str,
+        custom: &'a HashMap<String, ThemeConfig>,
+        seen: &mut Vec<&'a str>,
+    ) -> anyhow::Result<Self> {
+        if seen.contains(&name) {
+            bail!("Theme '{name}' is based on itself");
+        }
+        seen.push(name);
+
+        match custom.get(name) {
+            Some(config) => {
+                let mut theme =
+                    Self::resolve_with(config.base.as_deref().unwrap_or("default"), custom, seen)?;
+                config.apply(&mut theme);
+                Ok(theme)
+            }
+            None => Self::builtin(name).ok_or_else(|| {
+                anyhow!(
+                    "Unknown theme '{name}', built-in themes are: {}",
+                    Self::BUILTIN.join(", ")
+                )
+            }),
+        }
+    }
+
+    /// The default theme, for dark terminals with true color support
+    fn dark() -> Self {
+        Self {
+            header: Style::default().add_modifier(Modifier::BOLD),
+            selected: Style::default().add_modifier(Modifier::REVERSED),
+            marked: Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+            title: Style::default().fg(Color::White),
+            active_tab: Style::default()
+                .fg(Color::White)
+                .add_modifier(Modifier::BOLD | Modifier::REVERSED),
+            error: Style::default().bg(Color::Rgb(128, 0, 0)),
+            pod_pending: Style::default().bg(Color::Rgb(128, 0, 128)),
+            pod_warning: Style::default().bg(Color::Rgb(160, 80, 0)),
+            pod_error: Style::default()
+                .bg(Color::Rgb(128, 0, 0))
+                .add_modifier(Modifier::BOLD),
+            pod_crash_loop: Style::default().bg(Color::Rgb(128, 0, 0)),
+            pod_terminating: Style::default().bg(Color::Rgb(128, 128, 0)),
+            deployment_unavailable: Style::default().fg(Color::Red),
+            deployment_degraded: Style::default().fg(Color::Yellow),
+            logs: Style::default().fg(Color::White).bg(Color::Black),
+            log_error: Style::default().fg(Color::Red),
+            log_warn: Style::default().fg(Color::Yellow),
+            log_info: Style::default().fg(Color::Blue),
+            log_debug: Style::default().fg(Color::Green),
+            log_trace: Style::default().fg(Color::Gray),
+            manifest_key: Style::default().fg(Color::Cyan),
+            manifest_string: Style::default().fg(Color::Green),
+            manifest_literal: Style::default().fg(Color::Magenta),
+            manifest_null: Style::default().fg(Color::DarkGray),
+            search_match: Style::default().add_modifier(Modifier::REVERSED),
+            diff_insert: Style::default().fg(Color::Green),
+            diff_delete: Style::default().fg(Color::Red),
+        }
+    }
+
+    /// A theme using only the basic 16 colors
+    fn basic() -> Self {
+        Self {
+            error: Style::default().bg(Color::Red).fg(Color::White),
+            pod_pending: Style::default().bg(Color::Magenta).fg(Color::White),
+            pod_warning: Style::default().fg(Color::Yellow),
+            pod_error: Style::default()
+                .bg(Color::Red)
+                .fg(Color::White)
+                .add_modifier(Modifier::BOLD),
+            pod_crash_loop: Style::default().bg(Color::Red).fg(Color::White),
+            pod_terminating: Style::default().bg(Color::Yellow).fg(Color::Black),
+            ..Self::dark()
+        }
+    }
+
+    /// A theme for terminals with a light background
+    fn light() -> Self {
+        Self {
+            title: Style::default(),
+            active_tab: Style::default().add_modifier(Modifier::BOLD | Modifier::REVERSED),
+            marked: Style::default()
+                .fg(Color::Blue)
+                .add_modifier(Modifier::BOLD),
+            error: Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+            pod_pending: Style::default().fg(Color::Magenta),
+            pod_warning: Style::default().fg(Color::Rgb(200, 80, 0)),
+            pod_error: Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+            pod_crash_loop: Style::default().fg(Color::Red),
+            pod_terminating: Style::default().fg(Color::Rgb(160, 100, 0)),
+            deployment_unavailable: Style::default().fg(Color::Red),
+            deployment_degraded: Style::default().fg(Color::Rgb(160, 100, 0)),
+            logs: Style::default(),
+            log_warn: Style::default().fg(Color::Rgb(160, 100, 0)),
+            log_trace: Style::default().fg(Color::DarkGray),
+            manifest_key: Style::default().fg(Color::Blue),
+            manifest_string: Style::default().fg(Color::Rgb(0, 120, 0)),
+            manifest_null: Style::default().fg(Color::Gray),
+            diff_insert: Style::default().fg(Color::Rgb(0, 120, 0)),
+            ..Self::dark()
+        }
+    }
+
+    /// A theme without any colors, only using text modifiers
+    fn none() -> Self {
+        Self {
+            header: Style::default().add_modifier(Modifier::BOLD),
+            selected: Style::default().add_modifier(Modifier::REVERSED),
+            marked: Style::default().add_modifier(Modifier::BOLD | Modifier::ITALIC),
+            error: Style::default().add_modifier(Modifier::BOLD),
+            pod_pending: Style::default().add_modifier(Modifier::ITALIC),
+            pod_warning: Style::default().add_modifier(Modifier::UNDERLINED),
+            pod_error: Style::default().add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
+            pod_crash_loop: Style::default().add_modifier(Modifier::BOLD),
+            pod_terminating: Style::default().add_modifier(Modifier::DIM),
+            deployment_unavailable: Style::default().add_modifier(Modifier::BOLD),
+            deployment_degraded: Style::default().add_modifier(Modifier::ITALIC),
+            log_error: Style::default().add_modifier(Modifier::BOLD),
+            log_trace: Style::default().add_modifier(Modifier::DIM),
+            manifest_key: Style::default().add_modifier(Modifier::BOLD),
+            search_match: Style::default().add_modifier(Modifier::REVERSED),
+            diff_insert: Style::default().add_modifier(Modifier::BOLD),
+            diff_delete: Style::default().add_modifier(Modifier::CROSSED_OUT),
+            title: Style::default(),
+            active_tab: Style::default().add_modifier(Modifier::BOLD | Modifier::REVERSED),
+            logs: Style::default(),
+            log_warn: Style::default(),
+            log_info: Style::default(),
+            log_debug: Style::default(),
+            manifest_string: Style::default(),
+            manifest_literal: Style::default(),
+            manifest_null: Style::default(),
+        }
+    }
+}
+
+/// A style, as used in the configuration.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct StyleConfig {
+    #[serde(deserialize_with = "deserialize_color")]
+    pub fg: Option<Color>,
+    #[serde(deserialize_with = "deserialize_color")]
+    pub bg: Option<Color>,
+    pub bold: bool,
+    pub dim: bool,
+    pub italic: bool,
+    pub underlined: bool,
+    pub reversed: bool,
+}
+
+impl From<&StyleConfig> for Style {
+    fn from(config: &StyleConfig) -> Self {
+        let mut style = Style {
+            fg: config.fg,
+            bg: config.bg,
+            ..Default::default()
+        };
+
+        for (enabled, modifier) in [
+            (config.bold, Modifier::BOLD),
+            (config.dim, Modifier::DIM),
+            (config.italic, Modifier::ITALIC),
+            (config.underlined, Modifier::UNDERLINED),
+            (config.reversed, Modifier::REVERSED),
+        ] {
+            if enabled {
+                style = style.add_modifier(modifier);
+            }
+        }
+
+        style
+    }
+}
+
+fn deserialize_color<'de, D>(deserializer: D) -> Result<Option<Color>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Option::<String>::deserialize(deserializer)?
+        .map(|value| {
+            value
+                .parse::<Color>()
+                .map_err(|_| serde::de::Error::custom(format!("invalid color: {value}")))
+        })
+        .transpose()
+}
@@ -1,7 +1,7 @@
 use crate::Args;
 use k8s_openapi::NamespaceResourceScope;
 use kube::{
-    config::{KubeConfigOptions, KubeconfigError},
+    config::{KubeConfigOptions, Kubeconfig, KubeconfigError},
     Api, Resource,
 };
 use std::future::Future;
@@ -12,18 +12,97 @@ pub enum RunError<E> {
     Config(#[from] KubeconfigError),
     #[error("Failed to create client: {0}")]
     Kube(#[from] kube::Error),
-    #[error("Failed to create client: {0}")]
+    #[error("{0}")]
     Operation(#[source] E),
 }
 
+/// Where the client is connected to, as far as it is known.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ClusterInfo {
+    /// The name of the kubeconfig context
+    pub context: Option<String>,
+    /// The URL of the API server
+    pub server: Option<String>,
+    /// The namespace in use, resolving the default namespace of the context
+    pub namespace: Option<String>,
+    /// The version of the API server
+    pub version: Option<String>,
+}
+
 #[derive(Clone)]
 pub struct Client {
     args: Args,
+    client: Option<kube::Client>,
 }
 
 impl Client {
     pub fn new(args: Args) -> Self {
-        Self { args }
+        Self { args, client: None }
+    }
+
+    /// Create a client, using a provided Kubernetes client instead of the kubeconfig.
+    ///
+    /// This allows running against a different backend, like the fake API of the `testing` feature.
+    pub fn with_client(args: Args, client: kube::Client) -> Self {
+        Self {
+            args,
+            client: Some(client),
+        }
+    }
+
+    /// The namespace to use, `None` for the default namespace of the context.
+    pub fn namespace(&self) -> Option<&str> {
+        self.args.namespace.as_deref()
+    }
+
+    /// Gather information about the cluster, ignoring everything which can't be determined.
+    pub async fn info(&self) -> ClusterInfo {
+        let mut info = ClusterInfo::default();
+
+        // the context and server are only known when using the kubeconfig
+        if self.client.is_none() {
+            info.context = self.args.context.clone().or_else(|| {
+                Kubeconfig::read()
+                    .ok()
+                    .and_then(|kubeconfig| kubeconfig.current_context)
+            });
+            info.server = kube::Config::from_kubeconfig(&self.options())
+                .await
+                .ok()
+                .map(|config| config.cluster_url.to_string());
+        }
+
+        let result = self
+            .run(|ctx| {
+                let namespace = match &ctx.args.namespace {
+                    Some(namespace) => namespace.clone(),
+                    None => ctx.client.default_namespace().to_string(),
+                };
+                async move {
+                    let version = ctx.client.apiserver_version().await?;
+                    Ok::<_, kube::Error>((namespace, version.git_version))
+                }
+            })
+            .await;
+        match result {
+            Ok((namespace, version)) => {
+                info.namespace = Some(namespace);
+                info.version = Some(version);
+            }
+            Err(err) => {
+                log::warn!("Failed to get the version of the API server: {err}");
+                info.namespace = self.namespace().map(ToString::to_string);
+            }
+        }
+
+        info
+    }
+
+    fn options(&self) -> KubeConfigOptions {
+        KubeConfigOptions {
+            context: self.args.context.clone(),
+            ..Default::default()
+        }
     }
 
     pub async fn run<F, Fut, R, E>(&self, f: F) -> Result<R, RunError<E>>
@@ -31,14 +110,15 @@ impl Client {
         F: FnOnce(Context) -> Fut,
         Fut: Future<Output = Result<R, E>>,
     {
-        // right now, we just create a new client every time. later on, we should cache
-        // and invalidate the cache when an operation fails
-        let config = kube::Config::from_kubeconfig(&KubeConfigOptions {
-            context: self.args.context.clone(),
-            ..Default::default()
-        })
-        .await?;
-        let client = kube::Client::try_from(config)?;
+        let client = match &self.client {
+            Some(client) => client.clone(),
+            None => {
+                // right now, we just create a new client every time. later on, we should cache
+                // and invalidate the cache when an operation fails
+                let config = kube::Config::from_kubeconfig(&self.options()).await?;
+                kube::Client::try_from(config)?
+            }
+        };
 
         let context = Context {
             client,
@@ -0,0 +1,89 @@
+use crate::app::state::{columns::ColumnConfig, tabs::TabConfig, View};
+use crate::app::ui::theme::ThemeConfig;
+use crate::input::keymap::KeyConfig;
+use anyhow::Context;
+use serde::{Deserialize, Deserializer};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Persistent configuration, loaded from a file.
+///
+/// All values are optional, command line arguments and environment variables take precedence.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// Default namespace
+    pub namespace: Option<String>,
+    /// Default context
+    pub context: Option<String>,
+    /// The view to start with
+    pub view: Option<View>,
+    /// The minimum time between two redraws caused by changes
+    #[serde(deserialize_with = "deserialize_duration")]
+    pub render_rate: Option<Duration>,
+    /// Stop watching views which were not shown for this long, by default they are kept
+    #[serde(deserialize_with = "deserialize_duration")]
+    pub idle_timeout: Option<Duration>,
+    /// Key binding overrides, by scope and action
+    pub keys: KeyConfig,
+    /// The theme to use, built-in or custom
+    pub theme: Option<String>,
+    /// Custom themes
+    pub themes: HashMap<String, ThemeConfig>,
+    /// Column configuration, by view
+    pub columns: HashMap<View, ColumnConfig>,
+    /// Order and visibility of the tabs
+    pub tabs: TabConfig,
+}
+
+impl Config {
+    /// Load the configuration.
+    ///
+    /// If an explicit path is provided, the file must exist. Otherwise, the default location
+    /// is used, if it exists.
+    pub fn load(path: Option<&Path>) -> anyhow::Result<Self> {
+        match path {
+            Some(path) => Self::load_from(path),
+            None => match Self::default_path() {
+                Some(path) if path.exists() => Self::load_from(&path),
+                _ => Ok(Self::default()),
+            },
+        }
+    }
+
+    /// The default location of the configuration file
+    pub fn default_path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("podium").join("config.toml"))
+    }
+
+    fn load_from(path: &Path) -> anyhow::Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read configuration: {}", path.display()))?;
+        let config: Self = toml::from_str(&content)
+            .with_context(|| format!("Invalid configuration: {}", path.display()))?;
+        config
+            .validate()
+            .with_context(|| format!("Invalid configuration: {}", path.display()))?;
+        Ok(config)
+    }
+
+    fn validate(&self) -> anyhow::Result<()> {
+        for (view, columns) in &self.columns {
+            columns
+                .validate(view.columns())
+                .with_context(|| format!("Invalid column configuration for {view:?}"))?;
+        }
+        self.tabs.validate().context("Invalid tab configuration")?;
+        Ok(())
+    }
+}
+
+fn deserialize_duration<'de, D>(deserializer: D) -> Result<Option<Duration>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Option::<String>::deserialize(deserializer)?
+        .map(|value| humantime::parse_duration(&value).map_err(serde::de::Error::custom))
+        .transpose()
+}
@@ -1,67 +1,110 @@
 use crate::input::key::Key;
+use crate::input::mouse::Mouse;
 use crate::input::InputEvent;
-use std::sync::atomic::{AtomicBool, Ordering};
+use crossterm::event::{Event, EventStream};
+use futures::{future, StreamExt};
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::select;
-use tokio::time::{interval, Interval};
+use tokio::sync::Notify;
+use tokio::time::{interval, sleep_until, Instant, Interval, MissedTickBehavior};
 
-/// A small event handler that wrap crossterm input and tick event. Each event
-/// type is handled in its own thread and returned to a common `Receiver`
+/// The interval at which the UI is refreshed anyway, to keep relative times (like ages) current
+const REFRESH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// A handle to request redrawing the UI, e.g. when the content of a list changed.
+#[derive(Clone, Debug, Default)]
+pub struct Redraw(Arc<Notify>);
+
+impl Redraw {
+    /// Request a redraw. Requests are coalesced, so this is cheap to call often.
+    pub fn request(&self) {
+        self.0.notify_one();
+    }
+}
+
+/// The events driving the UI: terminal input, and requests to redraw.
+///
+/// Redraw requests are coalesced, rendering at most once per render rate. Without any input or
+/// changes, the UI is only refreshed once per [`REFRESH_INTERVAL`].
 pub struct Events {
-    rx: tokio::sync::mpsc::Receiver<InputEvent>,
-    // Need to be kept around to prevent disposing the sender side.
-    _tx: tokio::sync::mpsc::Sender<InputEvent>,
-    // To stop the loop
-    stop_capture: Arc<AtomicBool>,
-    // render interval
-    interval: Interval,
+    input: Option<EventStream>,
+    redraw: Redraw,
+    render_rate: Duration,
+    /// A pending redraw, and when to render it
+    pending: Option<Instant>,
+    refresh: Interval,
 }
 
 impl Events {
-    /// Constructs an new instance of `Events` with the default config.
-    pub fn new(render_rate: Duration) -> Events {
-        let (tx, rx) = tokio::sync::mpsc::channel(100);
-        let stop_capture = Arc::new(AtomicBool::new(false));
-
-        let event_tx = tx.clone();
-        let event_stop_capture = stop_capture.clone();
-        tokio::spawn(async move {
-            loop {
-                // poll for tick rate duration, if no event, sent tick event.
-                if crossterm::event::poll(render_rate).unwrap() {
-                    if let crossterm::event::Event::Key(key) = crossterm::event::read().unwrap() {
-                        let key = Key::from(key);
-                        if let Err(err) = event_tx.send(InputEvent::Input(key)).await {
-                            log::error!("Oops (event)!, {}", err);
-                        }
-                    }
-                }
-                if event_stop_capture.load(Ordering::Relaxed) {
-                    break;
-                }
-            }
-        });
+    pub fn new(render_rate: Duration, redraw: Redraw) -> Events {
+        let mut refresh = interval(REFRESH_INTERVAL);
+        refresh.set_missed_tick_behavior(MissedTickBehavior::Delay);
 
         Events {
-            rx,
-            _tx: tx,
-            stop_capture,
-            interval: interval(render_rate),
+            input: Some(EventStream::new()),
+            redraw,
+            render_rate,
+            pending: None,
+            refresh,
         }
     }
 
-    /// Attempts to read an event.
+    /// Stop capturing input, e.g. while an external program uses the terminal.
+    pub fn pause(&mut self) {
+        self.input = None;
+    }
+
+    /// Continue capturing input.
+    pub fn resume(&mut self) {
+        self.input = Some(EventStream::new());
+    }
+
+    /// Wait for the next event.
+    ///
+    /// The UI is expected to be rendered after each event.
     pub async fn next(&mut self) -> InputEvent {
-        select! {
-            msg = self.rx.recv() => msg.unwrap_or(InputEvent::Quit),
-            _ = self.interval.tick() => InputEvent::Render,
+        let event = self.wait().await;
+        self.pending = None;
+        event
+    }
+
+    async fn wait(&mut self) -> InputEvent {
+        loop {
+            let pending = self.pending;
+
+            select! {
+                event = next_input(&mut self.input) => match event {
+                    Some(Ok(Event::Key(key))) => return InputEvent::Input(Key::from(key)),
+                    Some(Ok(Event::Mouse(mouse))) => {
+                        if let Some(mouse) = Mouse::from_event(mouse) {
+                            return InputEvent::Mouse(mouse);
+                        }
+                    }
+                    Some(Ok(Event::Paste(text))) => return InputEvent::Paste(text),
+                    Some(Ok(Event::Resize(..))) => return InputEvent::Resize,
+                    Some(Ok(_)) => {}
+                    Some(Err(err)) => {
+                        log::error!("Failed to read input: {err}");
+                        return InputEvent::Quit;
+                    }
+                    None => return InputEvent::Quit,
+                },
+                _ = self.redraw.0.notified(), if pending.is_none() => {
+                    self.pending = Some(Instant::now() + self.render_rate);
+                }
+                _ = sleep_until(pending.unwrap_or_else(Instant::now)), if pending.is_some() => {
+                    return InputEvent::Render;
+                }
+                _ = self.refresh.tick() => return InputEvent::Render,
+            }
         }
     }
 }
 
-impl Drop for Events {
-    fn drop(&mut self) {
-        self.stop_capture.store(true, Ordering::Relaxed)
+async fn next_input(input: &mut Option<EventStream>) -> Option<std::io::Result<Event>> {
+    match input {
+        Some(input) => input.next().await,
+        None => future::pending().await,
     }
 }
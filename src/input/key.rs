@@ -1,4 +1,5 @@
 use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
 
 use crossterm::event;
 
@@ -115,6 +116,58 @@ impl Display for Key {
     }
 }
 
+impl FromStr for Key {
+    type Err = anyhow::Error;
+
+    /// Parse a key from its name, e.g. `q`, `ctrl-c`, `alt-x`, `esc`, `pagedown` or `f1`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars();
+        if let (Some(c), None) = (chars.next(), chars.next()) {
+            return Ok(Key::Char(c));
+        }
+
+        let lower = s.to_lowercase();
+        let single = |s: &str| {
+            let mut chars = s.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => Ok(c),
+                _ => Err(anyhow::anyhow!("Invalid key: {s}")),
+            }
+        };
+
+        if let Some(c) = lower.strip_prefix("ctrl-") {
+            return Ok(Key::Ctrl(single(c)?));
+        }
+        if let Some(c) = lower.strip_prefix("alt-") {
+            return Ok(Key::Alt(single(c)?));
+        }
+        if let Some(n) = lower.strip_prefix('f').and_then(|n| n.parse::<u8>().ok()) {
+            if n <= 12 {
+                return Ok(Key::from_f(n));
+            }
+        }
+
+        Ok(match lower.as_str() {
+            "enter" | "return" => Key::Enter,
+            "tab" => Key::Tab,
+            "backspace" => Key::Backspace,
+            "esc" | "escape" => Key::Esc,
+            "left" => Key::Left,
+            "right" => Key::Right,
+            "up" => Key::Up,
+            "down" => Key::Down,
+            "ins" | "insert" => Key::Ins,
+            "del" | "delete" => Key::Delete,
+            "home" => Key::Home,
+            "end" => Key::End,
+            "pageup" | "pgup" => Key::PageUp,
+            "pagedown" | "pgdown" => Key::PageDown,
+            "space" => Key::Char(' '),
+            _ => anyhow::bail!("Invalid key: {s}"),
+        })
+    }
+}
+
 impl From<event::KeyEvent> for Key {
     fn from(key_event: event::KeyEvent) -> Self {
         match key_event {
@@ -0,0 +1,625 @@
+use crate::input::key::Key;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+/// The scope in which a key binding is active.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Scope {
+    /// Always active, unless a popup is shown
+    Global,
+    /// All list views
+    List,
+    Overview,
+    Pods,
+    Deployments,
+    Secrets,
+    /// The manifest and the diff viewer
+    Viewer,
+    Manifest,
+    Diff,
+    /// Asking for confirmation of an action
+    Confirmation,
+    /// The options of deleting objects, while asking for confirmation
+    DeleteOptions,
+    /// The outcome of a bulk action
+    Report,
+    /// The label and annotation editor
+    Labels,
+    /// Applying the changes made in the external editor failed
+    EditFailure,
+}
+
+impl Scope {
+    const ALL: [Scope; 14] = [
+        Self::Global,
+        Self::List,
+        Self::Overview,
+        Self::Pods,
+        Self::Deployments,
+        Self::Secrets,
+        Self::Viewer,
+        Self::Manifest,
+        Self::Diff,
+        Self::Confirmation,
+        Self::DeleteOptions,
+        Self::Report,
+        Self::Labels,
+        Self::EditFailure,
+    ];
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            Self::Global => "Global",
+            Self::List => "Lists",
+            Self::Overview => "Overview",
+            Self::Pods => "Pods",
+            Self::Deployments => "Deployments",
+            Self::Secrets => "Secrets",
+            Self::Viewer => "Manifest and diff viewer",
+            Self::Manifest => "Manifest",
+            Self::Diff => "Diff",
+            Self::Confirmation => "Confirmation",
+            Self::DeleteOptions => "Deletion options",
+            Self::Report => "Report of bulk actions",
+            Self::Labels => "Labels and annotations",
+            Self::EditFailure => "Failed edit",
+        }
+    }
+}
+
+/// A named action, which can be bound to a key.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Action {
+    Quit,
+    Back,
+    Help,
+    ToggleLogs,
+    ToggleDebug,
+    ViewOverview,
+    ViewPods,
+    ViewDeployments,
+    ViewEvents,
+    ViewConfigMaps,
+    ViewSecrets,
+    PrevView,
+    NextView,
+    Tab1,
+    Tab2,
+    Tab3,
+    Tab4,
+    Tab5,
+    Tab6,
+    Tab7,
+    Tab8,
+    Tab9,
+    ShowManifest,
+    Edit,
+    ShowDiff,
+    EditLabels,
+
+    Up,
+    Down,
+    PageUp,
+    PageDown,
+    First,
+    Last,
+    ToggleMark,
+    MarkAll,
+    Delete,
+
+    Open,
+    KillPod,
+    ToggleDetails,
+    Restart,
+    ScaleUp,
+    ScaleDown,
+    RevealValues,
+
+    Close,
+    Search,
+    NextMatch,
+    PrevMatch,
+    ToggleFormat,
+    ToggleManagedFields,
+    ToggleStatus,
+    NextChange,
+    PrevChange,
+    TogglePruning,
+
+    Confirm,
+    Cancel,
+    ToggleEviction,
+    NextGracePeriod,
+    NextPropagation,
+
+    AddLabel,
+    AddAnnotation,
+    ChangeValue,
+    RemoveEntry,
+    Apply,
+}
+
+impl Action {
+    /// The position of the tab to switch to (starting at 0), for the tab actions.
+    pub fn tab(&self) -> Option<usize> {
+        let tabs = [
+            Self::Tab1,
+            Self::Tab2,
+            Self::Tab3,
+            Self::Tab4,
+            Self::Tab5,
+            Self::Tab6,
+            Self::Tab7,
+            Self::Tab8,
+            Self::Tab9,
+        ];
+        tabs.iter().position(|tab| tab == self)
+    }
+
+    /// A short label, for the actions worth a hint in the status bar.
+    pub fn hint(&self) -> Option<&'static str> {
+        Some(match self {
+            Self::Quit => "quit",
+            Self::Help => "help",
+            Self::ShowManifest => "manifest",
+            Self::Edit => "edit",
+            Self::ShowDiff => "diff",
+            Self::EditLabels => "labels",
+            Self::Open => "open",
+            Self::Delete => "delete",
+            Self::KillPod => "kill",
+            Self::ToggleDetails => "details",
+            Self::Restart => "restart",
+            Self::ScaleUp => "scale up",
+            Self::ScaleDown => "scale down",
+            Self::RevealValues => "reveal",
+            Self::Close => "close",
+            Self::Search => "search",
+            Self::NextMatch => "next match",
+            Self::PrevMatch => "previous match",
+            Self::ToggleFormat => "YAML/JSON",
+            Self::ToggleManagedFields => "managedFields",
+            Self::ToggleStatus => "status",
+            Self::NextChange => "next change",
+            Self::PrevChange => "previous change",
+            Self::TogglePruning => "pruning",
+            Self::Confirm => "confirm",
+            Self::Cancel => "cancel",
+            Self::AddLabel => "add label",
+            Self::AddAnnotation => "add annotation",
+            Self::ChangeValue => "change",
+            Self::RemoveEntry => "remove",
+            Self::Apply => "apply",
+            _ => return None,
+        })
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::Quit => "Exit the application",
+            Self::Back => "Exit the current view (or the application)",
+            Self::Help => "Toggle this help",
+            Self::ToggleLogs => "Toggle log view",
+            Self::ToggleDebug => "Toggle the list of live watchers",
+            Self::ViewOverview => "View overview",
+            Self::ViewPods => "View pods",
+            Self::ViewDeployments => "View deployments",
+            Self::ViewEvents => "View events",
+            Self::ViewConfigMaps => "View config maps",
+            Self::ViewSecrets => "View secrets",
+            Self::PrevView => "Previous view",
+            Self::NextView => "Next view",
+            Self::Tab1 => "View of the 1st tab",
+            Self::Tab2 => "View of the 2nd tab",
+            Self::Tab3 => "View of the 3rd tab",
+            Self::Tab4 => "View of the 4th tab",
+            Self::Tab5 => "View of the 5th tab",
+            Self::Tab6 => "View of the 6th tab",
+            Self::Tab7 => "View of the 7th tab",
+            Self::Tab8 => "View of the 8th tab",
+            Self::Tab9 => "View of the 9th tab",
+            Self::ShowManifest => "View manifest of selected object",
+            Self::Edit => "Edit selected object in $EDITOR",
+            Self::ShowDiff => {
+                "Diff selected object against last applied configuration (or --diff-file)"
+            }
+            Self::EditLabels => "Edit labels and annotations of selected object",
+            Self::Up => "Move up",
+            Self::Down => "Move down",
+            Self::PageUp => "Move a page up",
+            Self::PageDown => "Move a page down",
+            Self::First => "Move to the first entry",
+            Self::Last => "Move to the last entry",
+            Self::ToggleMark => "Mark or unmark selected entry, for bulk actions",
+            Self::MarkAll => "Mark all shown entries (or unmark them)",
+            Self::Delete => "Delete selected (or marked) objects",
+            Self::Open => "Open the list behind the selected tile",
+            Self::KillPod => "Delete or evict selected (or marked) pods",
+            Self::ToggleDetails => "Toggle details of selected pod",
+            Self::Restart => "Restart selected (or marked) deployments",
+            Self::ScaleUp => "Scale up selected (or marked) deployments",
+            Self::ScaleDown => "Scale down selected (or marked) deployments",
+            Self::RevealValues => "Reveal or hide secret values",
+            Self::Close => "Close the viewer or popup",
+            Self::Search => "Search",
+            Self::NextMatch => "Next match",
+            Self::PrevMatch => "Previous match",
+            Self::ToggleFormat => "Toggle YAML and JSON",
+            Self::ToggleManagedFields => "Toggle managedFields",
+            Self::ToggleStatus => "Toggle status",
+            Self::NextChange => "Next change",
+            Self::PrevChange => "Previous change",
+            Self::TogglePruning => "Toggle showing only fields of the desired state",
+            Self::Confirm => "Run the action",
+            Self::Cancel => "Don't run the action",
+            Self::ToggleEviction => "Evict pods instead of deleting them",
+            Self::NextGracePeriod => "Next grace period",
+            Self::NextPropagation => "Next propagation policy",
+            Self::AddLabel => "Add a label (as key=value)",
+            Self::AddAnnotation => "Add an annotation (as key=value)",
+            Self::ChangeValue => "Change the value of the selected entry",
+            Self::RemoveEntry => "Remove the selected entry (or keep it after all)",
+            Self::Apply => "Apply all changes",
+        }
+    }
+}
+
+/// A sequence of keys.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct KeySequence(pub Vec<Key>);
+
+impl FromStr for KeySequence {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let keys = s
+            .split_whitespace()
+            .map(Key::from_str)
+            .collect::<Result<Vec<_>, _>>()?;
+        if keys.is_empty() {
+            anyhow::bail!("Empty key sequence");
+        }
+        Ok(Self(keys))
+    }
+}
+
+impl Display for KeySequence {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        for (i, key) in self.0.iter().enumerate() {
+            if i > 0 {
+                f.write_str(" ")?;
+            }
+            write!(f, "{key}")?;
+        }
+        Ok(())
+    }
+}
+
+/// One or more key sequences, as used in the configuration.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(untagged)]
+pub enum KeyBindings {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl KeyBindings {
+    fn iter(&self) -> impl Iterator<Item = &String> {
+        match self {
+            Self::One(one) => std::slice::from_ref(one).iter(),
+            Self::Many(many) => many.iter(),
+        }
+    }
+}
+
+/// Key bindings overrides, by scope and action.
+///
+/// Ordered, so that overrides are applied in the same order every time.
+pub type KeyConfig = BTreeMap<Scope, BTreeMap<Action, KeyBindings>>;
+
+#[derive(Clone, Debug)]
+struct Binding {
+    scope: Scope,
+    keys: KeySequence,
+    action: Action,
+}
+
+/// The result of feeding a key into the keymap.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Resolved {
+    /// The key sequence resolved to an action
+    Action(Action),
+    /// The key is part of a longer sequence
+    Pending,
+    /// The key is not bound
+    Unbound,
+}
+
+/// The active key bindings.
+#[derive(Clone, Debug)]
+pub struct Keymap {
+    bindings: Vec<Binding>,
+    pending: Vec<Key>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        use Action::*;
+        use Scope::*;
+
+        let defaults: &[(Scope, Action, &[&str])] = &[
+            (Global, Quit, &["q", "ctrl-c"]),
+            (Global, Back, &["esc"]),
+            (Global, Help, &["h", "?"]),
+            (Global, ToggleLogs, &["l"]),
+            (Global, ToggleDebug, &["f12"]),
+            (Global, ViewOverview, &["o"]),
+            (Global, ViewPods, &["p"]),
+            (Global, ViewDeployments, &["d"]),
+            (Global, ViewEvents, &["E"]),
+            (Global, ViewConfigMaps, &["c"]),
+            (Global, ViewSecrets, &["s"]),
+            (Global, PrevView, &["left"]),
+            (Global, NextView, &["right"]),
+            (Global, Tab1, &["1"]),
+            (Global, Tab2, &["2"]),
+            (Global, Tab3, &["3"]),
+            (Global, Tab4, &["4"]),
+            (Global, Tab5, &["5"]),
+            (Global, Tab6, &["6"]),
+            (Global, Tab7, &["7"]),
+            (Global, Tab8, &["8"]),
+            (Global, Tab9, &["9"]),
+            (Global, ShowManifest, &["y"]),
+            (Global, Edit, &["e"]),
+            (Global, ShowDiff, &["D"]),
+            (Global, EditLabels, &["L"]),
+            (List, Down, &["down", "j"]),
+            (List, Up, &["up", "k"]),
+            (List, PageDown, &["pagedown", "ctrl-f"]),
+            (List, PageUp, &["pageup", "ctrl-b"]),
+            (List, First, &["home", "g g"]),
+            (List, Last, &["end", "G"]),
+            (List, ToggleMark, &["space"]),
+            (List, MarkAll, &["ctrl-a", "*"]),
+            (List, Delete, &["delete", "ctrl-d"]),
+            (Overview, Open, &["enter"]),
+            (Pods, KillPod, &["ctrl-k"]),
+            (Pods, ToggleDetails, &["i"]),
+            (Deployments, Restart, &["r"]),
+            (Deployments, ScaleUp, &["+"]),
+            (Deployments, ScaleDown, &["-"]),
+            (Secrets, RevealValues, &["v"]),
+            (Viewer, Close, &["esc", "q"]),
+            (Viewer, Quit, &["ctrl-c"]),
+            (Viewer, Down, &["down", "j"]),
+            (Viewer, Up, &["up", "k"]),
+            (Viewer, PageDown, &["pagedown", "ctrl-f"]),
+            (Viewer, PageUp, &["pageup", "ctrl-b"]),
+            (Viewer, First, &["home", "g g"]),
+            (Viewer, Last, &["end", "G"]),
+            (Manifest, Search, &["/"]),
+            (Manifest, NextMatch, &["n"]),
+            (Manifest, PrevMatch, &["N"]),
+            (Manifest, ToggleFormat, &["f"]),
+            (Manifest, ToggleManagedFields, &["m"]),
+            (Manifest, ToggleStatus, &["s"]),
+            (Manifest, RevealValues, &["v"]),
+            (Diff, NextChange, &["n"]),
+            (Diff, PrevChange, &["N"]),
+            (Diff, TogglePruning, &["p"]),
+            (Diff, RevealValues, &["v"]),
+            (Confirmation, Confirm, &["y", "enter"]),
+            (Confirmation, Cancel, &["n", "esc"]),
+            (Confirmation, Quit, &["ctrl-c"]),
+            (DeleteOptions, ToggleEviction, &["m"]),
+            (DeleteOptions, NextGracePeriod, &["g"]),
+            (DeleteOptions, NextPropagation, &["p"]),
+            (Report, Close, &["enter", "esc", "q"]),
+            (Report, Quit, &["ctrl-c"]),
+            (Labels, AddLabel, &["a"]),
+            (Labels, AddAnnotation, &["A"]),
+            (Labels, ChangeValue, &["e", "enter"]),
+            (Labels, RemoveEntry, &["d", "delete"]),
+            (Labels, Apply, &["y"]),
+            (Labels, Close, &["esc", "q"]),
+            (Labels, Quit, &["ctrl-c"]),
+            (Labels, Down, &["down", "j"]),
+            (Labels, Up, &["up", "k"]),
+            (EditFailure, Edit, &["e"]),
+            (EditFailure, Close, &["esc"]),
+            (EditFailure, Quit, &["ctrl-c"]),
+        ];
+
+        let bindings = defaults
+            .iter()
+            .flat_map(|(scope, action, keys)| {
+                keys.iter().map(|keys| Binding {
+                    scope: *scope,
+                    // the defaults are known to be valid
+                    keys: keys.parse().expect("valid default key binding"),
+                    action: *action,
+                })
+            })
+            .collect();
+
+        Self {
+            bindings,
+            pending: vec![],
+        }
+    }
+}
+
+impl Keymap {
+    /// Create a keymap from the defaults, with overrides from the configuration.
+    ///
+    /// Binding an action replaces all of its default bindings. A key sequence bound to an action
+    /// will no longer trigger other actions in the same scope. When the configuration binds the
+    /// same key sequence to several actions of a scope, the action declared last in [`Action`]
+    /// wins.
+    pub fn new(config: &KeyConfig) -> anyhow::Result<Self> {
+        let mut result = Self::default();
+
+        for (scope, actions) in config {
+            for (action, keys) in actions {
+                let keys = keys
+                    .iter()
+                    .map(|keys| {
+                        keys.parse::<KeySequence>().map_err(|err| {
+                            anyhow::anyhow!("Invalid key binding for '{action:?}': {err}")
+                        })
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                // the new bindings take the place of the defaults, keeping the order of the hints
+                let position = result
+                    .bindings
+                    .iter()
+                    .position(|b| b.scope == *scope && b.action == *action);
+                let position = position.map(|position| {
+                    result.bindings[..position]
+                        .iter()
+                        .filter(|b| b.scope != *scope || !keys.contains(&b.keys))
+                        .count()
+                });
+
+                result.bindings.retain(|b| {
+                    b.scope != *scope || (b.action != *action && !keys.contains(&b.keys))
+                });
+                let bindings = keys.into_iter().map(|keys| Binding {
+                    scope: *scope,
+                    keys,
+                    action: *action,
+                });
+                match position {
+                    Some(position) => {
+                        result.bindings.splice(position..position, bindings);
+                    }
+                    None => result.bindings.extend(bindings),
+                }
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// Feed a key, resolving it against the provided scopes, in order of precedence.
+    pub fn resolve(&mut self, key: Key, scopes: &[Scope]) -> Resolved {
+        self.pending.push(key);
+
+        match self.lookup(scopes) {
+            Resolved::Unbound if self.pending.len() > 1 => {
+                // the sequence didn't match, start over with the current key
+                self.pending.clear();
+                self.resolve(key, scopes)
+            }
+            Resolved::Pending => Resolved::Pending,
+            resolved => {
+                self.pending.clear();
+                resolved
+            }
+        }
+    }
+
+    fn lookup(&self, scopes: &[Scope]) -> Resolved {
+        for scope in scopes {
+            let mut pending = false;
+            for binding in self.bindings.iter().filter(|b| b.scope == *scope) {
+                if binding.keys.0 == self.pending {
+                    return Resolved::Action(binding.action);
+                }
+                if binding.keys.0.starts_with(&self.pending) {
+                    pending = true;
+                }
+            }
+
+            // a longer sequence of this scope takes precedence over the scopes below
+            if pending {
+                return Resolved::Pending;
+            }
+        }
+
+        Resolved::Unbound
+    }
+
+    /// The bindings of a scope, grouped by action, in order of definition.
+    pub fn bindings(&self, scope: Scope) -> Vec<(Action, Vec<&KeySequence>)> {
+        let mut result: Vec<(Action, Vec<&KeySequence>)> = vec![];
+
+        for binding in self.bindings.iter().filter(|b| b.scope == scope) {
+            match result
+                .iter_mut()
+                .find(|(action, _)| *action == binding.action)
+            {
+                Some((_, keys)) => keys.push(&binding.keys),
+                None => result.push((binding.action, vec![&binding.keys])),
+            }
+        }
+
+        result
+    }
+
+    /// The first key sequence bound to an action of a scope.
+    pub fn key(&self, scope: Scope, action: Action) -> Option<&KeySequence> {
+        self.bindings
+            .iter()
+            .find(|b| b.scope == scope && b.action == action)
+            .map(|b| &b.keys)
+    }
+
+    /// All scopes, which have bindings.
+    pub fn scopes(&self) -> impl Iterator<Item = Scope> + '_ {
+        Scope::ALL
+            .into_iter()
+            .filter(|scope| self.bindings.iter().any(|b| b.scope == *scope))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn config(scope: Scope, bindings: &[(Action, &str)]) -> KeyConfig {
+        let actions = bindings
+            .iter()
+            .map(|(action, keys)| (*action, KeyBindings::One(keys.to_string())))
+            .collect();
+        BTreeMap::from([(scope, actions)])
+    }
+
+    #[test]
+    fn pending_sequence_of_higher_scope() {
+        let mut keymap = Keymap::new(&config(Scope::Global, &[(Action::Help, "g")])).unwrap();
+        let scopes = [Scope::List, Scope::Global];
+
+        assert_eq!(keymap.resolve(Key::Char('g'), &scopes), Resolved::Pending);
+        assert_eq!(
+            keymap.resolve(Key::Char('g'), &scopes),
+            Resolved::Action(Action::First)
+        );
+
+        // without the list, the global binding applies
+        assert_eq!(
+            keymap.resolve(Key::Char('g'), &[Scope::Global]),
+            Resolved::Action(Action::Help)
+        );
+    }
+
+    #[test]
+    fn duplicate_bindings() {
+        let config = config(Scope::Global, &[(Action::Help, "x"), (Action::Quit, "x")]);
+
+        // the outcome doesn't depend on the order of the configuration
+        for _ in 0..10 {
+            let mut keymap = Keymap::new(&config).unwrap();
+            assert_eq!(
+                keymap.resolve(Key::Char('x'), &[Scope::Global]),
+                Resolved::Action(Action::Help)
+            );
+        }
+    }
+}
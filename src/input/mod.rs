@@ -1,12 +1,20 @@
-use crate::input::key::Key;
+use crate::input::{key::Key, mouse::Mouse};
 
 pub mod events;
 pub mod key;
+pub mod keymap;
+pub mod mouse;
 
 // inputs/mod.rs
 pub enum InputEvent {
     /// An input event occurred.
     Input(Key),
+    /// A mouse button was pressed, or the wheel scrolled.
+    Mouse(Mouse),
+    /// Text was pasted into the terminal.
+    Paste(String),
+    /// The terminal was resized.
+    Resize,
     /// Redraw application
     Render,
     /// Exit application
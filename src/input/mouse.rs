@@ -0,0 +1,34 @@
+use crossterm::event::{self, MouseButton, MouseEventKind};
+
+/// A mouse event, at a cell of the terminal.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub struct Mouse {
+    pub kind: MouseKind,
+    pub column: u16,
+    pub row: u16,
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum MouseKind {
+    /// The left button was pressed
+    Click,
+    ScrollUp,
+    ScrollDown,
+}
+
+impl Mouse {
+    pub fn new(kind: MouseKind, column: u16, row: u16) -> Self {
+        Self { kind, column, row }
+    }
+
+    /// Convert a terminal mouse event, ignoring the ones without any meaning (like moves).
+    pub fn from_event(event: event::MouseEvent) -> Option<Self> {
+        let kind = match event.kind {
+            MouseEventKind::Down(MouseButton::Left) => MouseKind::Click,
+            MouseEventKind::ScrollUp => MouseKind::ScrollUp,
+            MouseEventKind::ScrollDown => MouseKind::ScrollDown,
+            _ => return None,
+        };
+        Some(Self::new(kind, event.column, event.row))
+    }
+}
@@ -0,0 +1,449 @@
+//! An in-memory fake of the Kubernetes API, for testing.
+//!
+//! It implements just enough of the API for podium: listing, watching, getting, creating,
+//! replacing, patching and deleting namespaced objects. Every request is recorded, so that tests
+//! can check which operations were performed.
+
+use chrono::{SecondsFormat, Utc};
+use futures::{stream, StreamExt};
+use hyper::{body::Bytes, Body, Method, Request, Response, StatusCode};
+use kube::Resource;
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{json, Value};
+use std::{
+    collections::{BTreeMap, HashMap},
+    convert::Infallible,
+    sync::{Arc, Mutex},
+};
+use tokio::sync::mpsc::{unbounded_channel, UnboundedSender};
+
+/// A request received by the fake API.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RecordedRequest {
+    pub method: Method,
+    pub path: String,
+    pub body: Option<Value>,
+}
+
+/// An in-memory Kubernetes API.
+#[derive(Clone, Default)]
+pub struct FakeApi {
+    inner: Arc<Mutex<Inner>>,
+}
+
+#[derive(Default)]
+struct Inner {
+    /// Objects, by collection path (e.g. `/api/v1/namespaces/default/pods`) and name
+    objects: BTreeMap<String, BTreeMap<String, Value>>,
+    /// All changes, to replay them to watches starting at an older version
+    events: Vec<Change>,
+    /// Active watches, by collection path
+    watches: Vec<(String, UnboundedSender<Value>)>,
+    requests: Vec<RecordedRequest>,
+    version: u64,
+    /// Fail all requests, like an unreachable API server
+    offline: bool,
+    /// Refuse evictions, like a disruption budget not allowing any disruptions
+    evictions_blocked: bool,
+}
+
+struct Change {
+    version: u64,
+    collection: String,
+    event: Value,
+}
+
+/// The default namespace of the fake API.
+pub const NAMESPACE: &str = "default";
+
+impl FakeApi {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A client, talking to this fake API.
+    pub fn client(&self) -> kube::Client {
+        let api = self.clone();
+        let service = tower::service_fn(move |request: Request<Body>| {
+            let api = api.clone();
+            async move { Ok::<_, Infallible>(api.handle(request).await) }
+        });
+        kube::Client::new(service, NAMESPACE)
+    }
+
+    /// Create or update an object, notifying all watches.
+    pub fn apply<K>(&self, object: &K)
+    where
+        K: Resource<DynamicType = ()> + Serialize,
+    {
+        let collection = collection::<K>(object.meta().namespace.as_deref());
+        let object = serde_json::to_value(object).expect("objects must serialize");
+        self.inner.lock().unwrap().store(&collection, object);
+    }
+
+    /// Remove an object, notifying all watches.
+    pub fn remove<K>(&self, namespace: &str, name: &str)
+    where
+        K: Resource<DynamicType = ()>,
+    {
+        self.inner
+            .lock()
+            .unwrap()
+            .remove(&collection::<K>(Some(namespace)), name);
+    }
+
+    /// Get the current state of an object.
+    pub fn get<K>(&self, namespace: &str, name: &str) -> Option<K>
+    where
+        K: Resource<DynamicType = ()> + DeserializeOwned,
+    {
+        self.inner
+            .lock()
+            .unwrap()
+            .objects
+            .get(&collection::<K>(Some(namespace)))
+            .and_then(|objects| objects.get(name))
+            .and_then(|object| serde_json::from_value(object.clone()).ok())
+    }
+
+    /// Simulate an unreachable API server, failing all requests and closing all watches.
+    pub fn set_offline(&self, offline: bool) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.offline = offline;
+        if offline {
+            inner.watches.clear();
+        }
+    }
+
+    /// Refuse all evictions, as if they violated a PodDisruptionBudget.
+    pub fn set_evictions_blocked(&self, blocked: bool) {
+        self.inner.lock().unwrap().evictions_blocked = blocked;
+    }
+
+    /// All requests received so far.
+    pub fn requests(&self) -> Vec<RecordedRequest> {
+        self.inner.lock().unwrap().requests.clone()
+    }
+
+    async fn handle(&self, request: Request<Body>) -> Response<Body> {
+        let (parts, body) = request.into_parts();
+        let body = hyper::body::to_bytes(body).await.unwrap_or_default();
+        let body = serde_json::from_slice::<Value>(&body).ok();
+
+        let path = parts.uri.path().to_string();
+        let query = parts
+            .uri
+            .query()
+            .unwrap_or_default()
+            .split('&')
+            .filter_map(|p| p.split_once('='))
+            .collect::<HashMap<_, _>>();
+
+        let mut inner = self.inner.lock().unwrap();
+        inner.requests.push(RecordedRequest {
+            method: parts.method.clone(),
+            path: path.clone(),
+            body: body.clone(),
+        });
+
+        if inner.offline {
+            return status(StatusCode::SERVICE_UNAVAILABLE, "API server unavailable");
+        }
+
+        if path == "/version" {
+            return json_response(
+                StatusCode::OK,
+                &json!({
+                    "major": "1",
+                    "minor": "29",
+                    "gitVersion": "v1.29.0-fake",
+                    "gitCommit": "",
+                    "gitTreeState": "clean",
+                    "buildDate": "",
+                    "goVersion": "",
+                    "compiler": "",
+                    "platform": "",
+                }),
+            );
+        }
+
+        // split into the collection, and the optional name
+        let segments = path.trim_matches('/').split('/').collect::<Vec<_>>();
+        let Some(idx) = segments.iter().position(|s| *s == "namespaces") else {
+            return status(
+                StatusCode::NOT_FOUND,
+                "Only namespaced resources are supported",
+            );
+        };
+        if segments.len() < idx + 3 {
+            return status(StatusCode::NOT_FOUND, "Missing resource type");
+        }
+        let collection = format!("/{}", segments[..idx + 3].join("/"));
+        let api_version = segments[1..idx].join("/");
+
+        match (&parts.method, &segments[idx + 3..]) {
+            (&Method::GET, []) if query.get("watch") == Some(&"true") => {
+                let version = query
+                    .get("resourceVersion")
+                    .and_then(|v| v.parse().ok())
+                    .unwrap_or_default();
+                inner.watch(collection, version)
+            }
+            (&Method::GET, []) => {
+                let items = inner
+                    .objects
+                    .get(&collection)
+                    .map(|objects| objects.values().cloned().collect::<Vec<_>>())
+                    .unwrap_or_default();
+                json_response(
+                    StatusCode::OK,
+                    &json!({
+                        "apiVersion": api_version,
+                        "kind": "List",
+                        "metadata": { "resourceVersion": inner.version.to_string() },
+                        "items": items,
+                    }),
+                )
+            }
+            (&Method::POST, []) => {
+                let Some(object) = body else {
+                    return status(StatusCode::BAD_REQUEST, "Missing object");
+                };
+                let name = object["metadata"]["name"].as_str().unwrap_or_default();
+                if inner.get(&collection, name).is_some() {
+                    return status(StatusCode::CONFLICT, "Object already exists");
+                }
+                let object = inner.store(&collection, object);
+                json_response(StatusCode::CREATED, &object)
+            }
+            (&Method::POST, [name, "eviction"]) => {
+                let Some(current) = inner.get(&collection, name).cloned() else {
+                    return status(StatusCode::NOT_FOUND, &format!("{name} not found"));
+                };
+                let options = body.map(|b| b["deleteOptions"].clone());
+                if let Some(response) = failed_precondition(&current, options.as_ref()) {
+                    return response;
+                }
+                if inner.evictions_blocked {
+                    return status(
+                        StatusCode::TOO_MANY_REQUESTS,
+                        "Cannot evict pod as it would violate the pod's disruption budget.",
+                    );
+                }
+                inner.remove(&collection, name);
+                json_response(
+                    StatusCode::CREATED,
+                    &json!({ "kind": "Status", "apiVersion": "v1", "status": "Success" }),
+                )
+            }
+            (method, [name]) => {
+                let Some(current) = inner.get(&collection, name).cloned() else {
+                    return status(StatusCode::NOT_FOUND, &format!("{name} not found"));
+                };
+
+                match *method {
+                    Method::GET => json_response(StatusCode::OK, &current),
+                    Method::PUT => {
+                        let Some(object) = body else {
+                            return status(StatusCode::BAD_REQUEST, "Missing object");
+                        };
+                        if let Some(version) = object["metadata"]["resourceVersion"].as_str() {
+                            if current["metadata"]["resourceVersion"].as_str() != Some(version) {
+                                return status(StatusCode::CONFLICT, "Object has been modified");
+                            }
+                        }
+                        let object = inner.store(&collection, object);
+                        json_response(StatusCode::OK, &object)
+                    }
+                    Method::PATCH => {
+                        let Some(patch) = body else {
+                            return status(StatusCode::BAD_REQUEST, "Missing patch");
+                        };
+                        if let Some(version) = patch["metadata"]["resourceVersion"].as_str() {
+                            if current["metadata"]["resourceVersion"].as_str() != Some(version) {
+                                return status(StatusCode::CONFLICT, "Object has been modified");
+                            }
+                        }
+                        // strategic merge patches are treated like JSON merge patches, which
+                        // is good enough for what podium sends
+                        let mut object = current;
+                        merge(&mut object, &patch);
+                        let object = inner.store(&collection, object);
+                        json_response(StatusCode::OK, &object)
+                    }
+                    Method::DELETE => {
+                        if let Some(response) = failed_precondition(&current, body.as_ref()) {
+                            return response;
+                        }
+                        inner.remove(&collection, name);
+                        json_response(StatusCode::OK, &current)
+                    }
+                    _ => status(StatusCode::METHOD_NOT_ALLOWED, "Method not allowed"),
+                }
+            }
+            _ => status(StatusCode::NOT_FOUND, "Not supported by the fake API"),
+        }
+    }
+}
+
+impl Inner {
+    fn get(&self, collection: &str, name: &str) -> Option<&Value> {
+        self.objects.get(collection).and_then(|o| o.get(name))
+    }
+
+    /// Store an object, filling in the server side fields.
+    fn store(&mut self, collection: &str, mut object: Value) -> Value {
+        self.version += 1;
+
+        let name = object["metadata"]["name"]
+            .as_str()
+            .unwrap_or_default()
+            .to_string();
+        let existing = self.get(collection, &name).cloned();
+
+        let metadata = &mut object["metadata"];
+        metadata["resourceVersion"] = self.version.to_string().into();
+        if metadata["uid"].is_null() {
+            metadata["uid"] = match &existing {
+                Some(existing) => existing["metadata"]["uid"].clone(),
+                None => format!("uid-{}", self.version).into(),
+            };
+        }
+        if metadata["creationTimestamp"].is_null() {
+            metadata["creationTimestamp"] =
+                Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true).into();
+        }
+
+        let r#type = match existing {
+            Some(_) => "MODIFIED",
+            None => "ADDED",
+        };
+        self.objects
+            .entry(collection.to_string())
+            .or_default()
+            .insert(name, object.clone());
+        self.notify(collection, r#type, &object);
+
+        object
+    }
+
+    fn remove(&mut self, collection: &str, name: &str) {
+        if let Some(mut object) = self
+            .objects
+            .get_mut(collection)
+            .and_then(|o| o.remove(name))
+        {
+            self.version += 1;
+            object["metadata"]["resourceVersion"] = self.version.to_string().into();
+            self.notify(collection, "DELETED", &object);
+        }
+    }
+
+    fn notify(&mut self, collection: &str, r#type: &str, object: &Value) {
+        let event = json!({ "type": r#type, "object": object });
+
+        self.watches
+            .retain(|(c, tx)| c != collection || tx.send(event.clone()).is_ok());
+        self.events.push(Change {
+            version: self.version,
+            collection: collection.to_string(),
+            event,
+        });
+    }
+
+    /// Start a watch, replaying all changes since the provided version.
+    fn watch(&mut self, collection: String, version: u64) -> Response<Body> {
+        let replay = self
+            .events
+            .iter()
+            .filter(|c| c.collection == collection && c.version > version)
+            .map(|c| c.event.clone())
+            .collect::<Vec<_>>();
+
+        let (tx, mut rx) = unbounded_channel();
+        self.watches.push((collection, tx));
+
+        let live = stream::poll_fn(move |cx| rx.poll_recv(cx));
+        let events = stream::iter(replay).chain(live).map(|event| {
+            let mut line = serde_json::to_vec(&event).unwrap_or_default();
+            line.push(b'\n');
+            Ok::<_, Infallible>(Bytes::from(line))
+        });
+
+        Response::builder()
+            .status(StatusCode::OK)
+            .header("content-type", "application/json")
+            .body(Body::wrap_stream(events))
+            .unwrap_or_default()
+    }
+}
+
+/// The collection path of a resource type.
+fn collection<K>(namespace: Option<&str>) -> String
+where
+    K: Resource<DynamicType = ()>,
+{
+    K::url_path(&(), Some(namespace.unwrap_or(NAMESPACE)))
+}
+
+/// Apply a JSON merge patch (RFC 7386).
+fn merge(target: &mut Value, patch: &Value) {
+    match patch {
+        Value::Object(patch) => {
+            if !target.is_object() {
+                *target = json!({});
+            }
+            if let Value::Object(target) = target {
+                for (key, value) in patch {
+                    match value {
+                        Value::Null => {
+                            target.remove(key);
+                        }
+                        value => merge(target.entry(key.clone()).or_insert(Value::Null), value),
+                    }
+                }
+            }
+        }
+        patch => *target = patch.clone(),
+    }
+}
+
+/// Check the preconditions of delete options, returning the response to reject the request.
+fn failed_precondition(current: &Value, options: Option<&Value>) -> Option<Response<Body>> {
+    let preconditions = options
+        .map(|o| o["preconditions"].clone())
+        .unwrap_or_default();
+    for field in ["uid", "resourceVersion"] {
+        if let Some(expected) = preconditions[field].as_str() {
+            if current["metadata"][field].as_str() != Some(expected) {
+                return Some(status(
+                    StatusCode::CONFLICT,
+                    &format!("Precondition failed: {field}"),
+                ));
+            }
+        }
+    }
+    None
+}
+
+fn json_response(code: StatusCode, value: &Value) -> Response<Body> {
+    Response::builder()
+        .status(code)
+        .header("content-type", "application/json")
+        .body(Body::from(serde_json::to_vec(value).unwrap_or_default()))
+        .unwrap_or_default()
+}
+
+fn status(code: StatusCode, message: &str) -> Response<Body> {
+    json_response(
+        code,
+        &json!({
+            "apiVersion": "v1",
+            "kind": "Status",
+            "status": "Failure",
+            "message": message,
+            "reason": code.canonical_reason().unwrap_or_default(),
+            "code": code.as_u16(),
+        }),
+    )
+}
@@ -1,9 +1,13 @@
+#[cfg(any(test, feature = "testing"))]
+pub mod fake;
+mod quantity;
 mod reflector;
 mod scale;
 
 use chrono::Utc;
 use humantime::format_duration;
 use k8s_openapi::apimachinery::pkg::apis::meta::v1::Time;
+pub use quantity::*;
 pub use reflector::*;
 pub use scale::*;
 
@@ -0,0 +1,92 @@
+use k8s_openapi::apimachinery::pkg::api::resource::Quantity;
+
+/// Parse a quantity (like `100m`, `1.5`, `128Mi` or `1e3`) into its numeric value.
+pub fn parse_quantity(quantity: &Quantity) -> Option<f64> {
+    const SUFFIXES: [(&str, f64); 16] = [
+        ("Ki", 1024.0),
+        ("Mi", 1_048_576.0),
+        ("Gi", 1_073_741_824.0),
+        ("Ti", 1_099_511_627_776.0),
+        ("Pi", 1_125_899_906_842_624.0),
+        ("Ei", 1_152_921_504_606_846_976.0),
+        ("n", 1e-9),
+        ("u", 1e-6),
+        ("m", 1e-3),
+        ("k", 1e3),
+        ("M", 1e6),
+        ("G", 1e9),
+        ("T", 1e12),
+        ("P", 1e15),
+        ("E", 1e18),
+        ("", 1.0),
+    ];
+
+    let value = quantity.0.trim();
+    // a plain number might also use an exponent, like `1e3`
+    if let Ok(value) = value.parse::<f64>() {
+        return Some(value);
+    }
+
+    SUFFIXES.iter().find_map(|(suffix, factor)| {
+        let number = value.strip_suffix(suffix)?;
+        number.parse::<f64>().ok().map(|n| n * factor)
+    })
+}
+
+/// Format a number of CPU cores, using millicores for small values.
+pub fn format_cpu(cores: f64) -> String {
+    if cores < 1.0 {
+        format!("{}m", (cores * 1000.0).round())
+    } else {
+        format!("{:.2}", cores)
+            .trim_end_matches('0')
+            .trim_end_matches('.')
+            .to_string()
+    }
+}
+
+/// Format a number of bytes, using binary units.
+pub fn format_bytes(bytes: f64) -> String {
+    const UNITS: [&str; 6] = ["", "Ki", "Mi", "Gi", "Ti", "Pi"];
+
+    let mut value = bytes;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+
+    let value = format!("{value:.1}");
+    format!("{}{}", value.trim_end_matches(".0"), UNITS[unit])
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn parse(value: &str) -> Option<f64> {
+        parse_quantity(&Quantity(value.to_string()))
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(parse("2"), Some(2.0));
+        assert_eq!(parse("250m"), Some(0.25));
+        assert_eq!(parse("1.5"), Some(1.5));
+        assert_eq!(parse("128Mi"), Some(128.0 * 1024.0 * 1024.0));
+        assert_eq!(parse("1G"), Some(1e9));
+        assert_eq!(parse("1e3"), Some(1000.0));
+        assert_eq!(parse("1Ei"), Some(1024f64.powi(6)));
+        assert_eq!(parse("12Q"), None);
+    }
+
+    #[test]
+    fn test_format() {
+        assert_eq!(format_cpu(0.25), "250m");
+        assert_eq!(format_cpu(1.0), "1");
+        assert_eq!(format_cpu(2.5), "2.5");
+        assert_eq!(format_bytes(512.0), "512");
+        assert_eq!(format_bytes(1.5 * 1024.0 * 1024.0 * 1024.0), "1.5Gi");
+        assert_eq!(format_bytes(128.0 * 1024.0 * 1024.0), "128Mi");
+    }
+}
@@ -32,12 +32,19 @@ where
     K::DynamicType: Clone + Default + Hash + Eq,
 {
     pub async fn new(client: &Client) -> anyhow::Result<Reflector<K>> {
+        Self::with_config(client, Default::default()).await
+    }
+
+    /// Create a new reflector, using a specific watcher configuration (e.g. a label selector).
+    pub async fn with_config(
+        client: &Client,
+        config: watcher::Config,
+    ) -> anyhow::Result<Reflector<K>> {
         Ok(client
             .run(|context| {
                 let pods: Api<K> = context.api_namespaced();
                 async {
                     let (reader, writer) = reflector::store();
-                    let config = Default::default();
                     let stream = Box::pin(reflector(writer, watcher(pods, config)));
                     Ok::<_, Infallible>(Reflector { reader, stream })
                 }
@@ -1,35 +1,96 @@
 #![allow(async_fn_in_trait)]
 
 pub mod app;
+pub mod client;
+pub mod config;
 pub mod input;
 pub mod k8s;
+pub mod output;
+pub mod wait;
 
-mod client;
-
-use crate::app::{ui, AppReturn};
+use crate::app::{state::View, ui, AppReturn};
+use crate::config::Config;
 use crate::input::events::Events;
 use crate::input::InputEvent;
+use crate::output::OutputFormat;
+use crate::wait::WaitArgs;
 use app::App;
 use clap::Parser;
+use crossterm::event::{
+    DisableBracketedPaste, DisableMouseCapture, EnableBracketedPaste, EnableMouseCapture,
+};
+use crossterm::execute;
 use ratatui::backend::CrosstermBackend;
 use ratatui::Terminal;
 use std::io::stdout;
+use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Duration;
 
+const DEFAULT_RENDER_RATE: Duration = Duration::from_millis(200);
+
 /// Watch pods
 #[derive(Parser, Debug, Clone)]
 #[clap(author, version, about, long_about = None)]
 pub struct Args {
     /// Namespace
-    #[clap(short, long, value_parser)]
+    #[clap(short, long, value_parser, global = true, env = "PODIUM_NAMESPACE")]
     pub namespace: Option<String>,
     /// Context
-    #[clap(short, long, value_parser)]
+    #[clap(short, long, value_parser, global = true, env = "PODIUM_CONTEXT")]
     pub context: Option<String>,
+    /// The view to start with
+    #[clap(long, value_enum, env = "PODIUM_VIEW")]
+    pub view: Option<View>,
+    /// The minimum time between two redraws caused by changes (e.g. 200ms)
+    #[clap(long, value_parser = humantime::parse_duration, env = "PODIUM_RENDER_RATE")]
+    pub render_rate: Option<Duration>,
+    /// The color theme: default, 16, light, none or a custom theme [default: default, none if NO_COLOR is set]
+    #[clap(long, value_parser, env = "PODIUM_THEME")]
+    pub theme: Option<String>,
+    /// Configuration file [default: ~/.config/podium/config.toml]
+    #[clap(long, value_parser, global = true, env = "PODIUM_CONFIG")]
+    pub config: Option<PathBuf>,
     /// Verbose
     #[clap(short, long, action = clap::ArgAction::Count)]
     pub verbose: u8,
+    /// Local manifest file to diff objects against, instead of their last applied configuration
+    #[clap(long, value_parser)]
+    pub diff_file: Option<PathBuf>,
+    /// Print the current state of the view once and exit, instead of running the UI
+    #[clap(long)]
+    pub once: bool,
+    /// Format of the printed output, implies --once unless --watch is used
+    #[clap(short, long, value_enum)]
+    pub output: Option<OutputFormat>,
+    /// Print changes of the view as events, instead of running the UI
+    #[clap(long, conflicts_with = "once")]
+    pub watch: bool,
+    #[clap(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(clap::Subcommand, Debug, Clone)]
+pub enum Command {
+    /// Wait until pods or deployments reach a condition, instead of running the UI
+    Wait(WaitArgs),
+}
+
+impl Args {
+    /// Fill in all values not provided as arguments from the configuration.
+    pub fn merge(mut self, config: &Config) -> Self {
+        self.namespace = self.namespace.or_else(|| config.namespace.clone());
+        self.context = self.context.or_else(|| config.context.clone());
+        self.view = self.view.or(config.view);
+        self.render_rate = self.render_rate.or(config.render_rate);
+        self.theme = self.theme.or_else(|| config.theme.clone());
+        self
+    }
+
+    /// Whether to print to stdout, instead of running the UI.
+    pub fn is_batch(&self) -> bool {
+        self.once || self.watch || self.output.is_some()
+    }
 }
 
 pub async fn start_ui(app: &Arc<tokio::sync::Mutex<App>>) -> anyhow::Result<()> {
@@ -38,11 +99,22 @@ pub async fn start_ui(app: &Arc<tokio::sync::Mutex<App>>) -> anyhow::Result<()>
     crossterm::terminal::enable_raw_mode()?;
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
+    execute!(
+        terminal.backend_mut(),
+        EnableMouseCapture,
+        EnableBracketedPaste
+    )?;
     terminal.clear()?;
     terminal.hide_cursor()?;
 
-    let render_rate = Duration::from_millis(200);
-    let mut events = Events::new(render_rate);
+    let render_rate = app
+        .lock()
+        .await
+        .args()
+        .render_rate
+        .unwrap_or(DEFAULT_RENDER_RATE);
+    let redraw = app.lock().await.registry().redraw().clone();
+    let mut events = Events::new(render_rate, redraw);
 
     loop {
         // Handle inputs
@@ -51,10 +123,42 @@ pub async fn start_ui(app: &Arc<tokio::sync::Mutex<App>>) -> anyhow::Result<()>
         {
             let mut app = app.lock().await;
             match event {
-                InputEvent::Input(key) => {
-                    if let AppReturn::Exit = app.do_action(key).await {
-                        break;
+                InputEvent::Input(key) => match app.do_action(key).await {
+                    AppReturn::Exit => break,
+                    AppReturn::Continue => {}
+                    AppReturn::Edit(mut edit) => {
+                        // hand over the terminal to the editor
+                        events.pause();
+                        execute!(
+                            terminal.backend_mut(),
+                            DisableMouseCapture,
+                            DisableBracketedPaste
+                        )?;
+                        terminal.clear()?;
+                        terminal.show_cursor()?;
+                        crossterm::terminal::disable_raw_mode()?;
+
+                        let result = edit.run_editor();
+
+                        crossterm::terminal::enable_raw_mode()?;
+                        execute!(
+                            terminal.backend_mut(),
+                            EnableMouseCapture,
+                            EnableBracketedPaste
+                        )?;
+                        terminal.hide_cursor()?;
+                        terminal.clear()?;
+                        events.resume();
+
+                        app.apply_edit(edit, result).await;
                     }
+                },
+                InputEvent::Mouse(mouse) => app.on_mouse(mouse).await,
+                InputEvent::Paste(text) => app.on_paste(&text),
+                InputEvent::Resize => {
+                    // repaint everything, as the content of the old size is gone
+                    terminal.autoresize()?;
+                    terminal.clear()?;
                 }
                 InputEvent::Render => {}
                 InputEvent::Quit => {
@@ -62,11 +166,17 @@ pub async fn start_ui(app: &Arc<tokio::sync::Mutex<App>>) -> anyhow::Result<()>
                 }
             }
             // always render after a change
+            app.poll();
             terminal.draw(|rect| ui::draw(rect, &app))?;
         }
     }
 
     // Restore the terminal and close application
+    execute!(
+        terminal.backend_mut(),
+        DisableMouseCapture,
+        DisableBracketedPaste
+    )?;
     terminal.clear()?;
     terminal.show_cursor()?;
     crossterm::terminal::disable_raw_mode()?;
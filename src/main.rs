@@ -1,13 +1,36 @@
 use clap::Parser;
 use log::LevelFilter;
-use podium::{app::App, start_ui, Args};
+use podium::{
+    app::{
+        ui::theme::{default_theme_name, set_theme, Theme},
+        App,
+    },
+    config::Config,
+    start_ui, Args, Command,
+};
 use std::sync::Arc;
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     let args = Args::parse();
+    let config = Config::load(args.config.as_deref())?;
+    let args = args.merge(&config);
 
-    let app = Arc::new(tokio::sync::Mutex::new(App::new(args.clone())));
+    if let Some(Command::Wait(wait)) = &args.command {
+        return podium::wait::run(&args, wait).await;
+    }
+    if args.is_batch() {
+        return podium::output::run(&args, &config).await;
+    }
+
+    set_theme(Theme::resolve(
+        args.theme
+            .as_deref()
+            .unwrap_or_else(|| default_theme_name()),
+        &config.themes,
+    )?);
+
+    let app = Arc::new(tokio::sync::Mutex::new(App::new(args.clone(), &config)?));
     let app_ui = Arc::clone(&app);
 
     // Configure log
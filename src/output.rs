@@ -0,0 +1,275 @@
+//! Non-interactive output, for scripts and CI.
+
+use crate::app::state::{
+    columns::{ColumnConfig, RowContent},
+    config::{ConfigMaps, Secrets},
+    list::ListResource,
+    Deployments, Events, View,
+};
+use crate::client::Client;
+use crate::config::Config;
+use crate::Args;
+use anyhow::bail;
+use futures::{StreamExt, TryStreamExt};
+use k8s_openapi::api::core::v1::Pod;
+use kube::{
+    runtime::{watcher, WatchStreamExt},
+    Api, Resource, ResourceExt,
+};
+use serde_json::{Map, Value};
+use std::{
+    collections::HashMap,
+    convert::Infallible,
+    io::{stdout, Write},
+    sync::Arc,
+};
+
+/// The format of the non-interactive output
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum OutputFormat {
+    /// Aligned columns, like `kubectl get`
+    #[default]
+    Plain,
+    /// JSON, one line per event when watching
+    Json,
+    /// CSV, with a header line
+    Csv,
+}
+
+/// The kind of change, when watching
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum EventType {
+    Added,
+    Modified,
+    Deleted,
+}
+
+impl EventType {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Added => "ADDED",
+            Self::Modified => "MODIFIED",
+            Self::Deleted => "DELETED",
+        }
+    }
+}
+
+/// Print the selected view to stdout, instead of running the UI.
+pub async fn run(args: &Args, config: &Config) -> anyhow::Result<()> {
+    let view = args.view.unwrap_or(View::Pods);
+    let columns = config.columns.get(&view).cloned().unwrap_or_default();
+
+    match view {
+        View::Overview => bail!("The overview is only available in the UI"),
+        View::Pods => run_view::<Pod>(args, columns).await,
+        View::Deployments => run_view::<Deployments>(args, columns).await,
+        View::Events => run_view::<Events>(args, columns).await,
+        View::ConfigMaps => run_view::<ConfigMaps>(args, columns).await,
+        View::Secrets => run_view::<Secrets>(args, columns).await,
+    }
+}
+
+async fn run_view<R: ListResource>(args: &Args, columns: ColumnConfig) -> anyhow::Result<()>
+where
+    <R::Resource as Resource>::DynamicType: Default,
+{
+    let client = Client::new(args.clone());
+    let api: Api<R::Resource> = client
+        .run(|context| {
+            let api = context.api_namespaced();
+            async { Ok::<_, Infallible>(api) }
+        })
+        .await?;
+
+    let mut printer = Printer::new(args.output.unwrap_or_default(), columns.header(R::COLUMNS));
+
+    if !args.watch {
+        let mut items = api
+            .list(&Default::default())
+            .await?
+            .items
+            .into_iter()
+            .map(Arc::new)
+            .collect::<Vec<_>>();
+        R::sort(&mut items);
+
+        let rows = columns.rows(R::COLUMNS, &items, R::make_row);
+        return printer.table(rows);
+    }
+
+    // the last seen resource version, by name
+    let mut known = HashMap::<String, String>::new();
+    let mut stream = watcher(api, Default::default()).default_backoff().boxed();
+
+    loop {
+        let event = match stream.try_next().await {
+            Ok(Some(event)) => event,
+            Ok(None) => break,
+            Err(err) => {
+                eprintln!("Watch error: {err}");
+                continue;
+            }
+        };
+
+        let mut changes = vec![];
+        match event {
+            watcher::Event::Applied(item) => changes.extend(applied(&mut known, item)),
+            watcher::Event::Deleted(item) => {
+                known.remove(&item.name_any());
+                changes.push((EventType::Deleted, Arc::new(item)));
+            }
+            watcher::Event::Restarted(mut items) => {
+                // after a re-list, report everything which changed in the meantime
+                items.sort_unstable_by_key(|item| item.name_any());
+                let mut removed = known.clone();
+                for item in items {
+                    removed.remove(&item.name_any());
+                    changes.extend(applied(&mut known, item));
+                }
+                for name in removed.into_keys() {
+                    known.remove(&name);
+                    let mut item = R::Resource::default();
+                    item.meta_mut().name = Some(name);
+                    changes.push((EventType::Deleted, Arc::new(item)));
+                }
+            }
+        }
+
+        for (event, item) in changes {
+            let row = columns
+                .rows(R::COLUMNS, &[item], R::make_row)
+                .into_iter()
+                .next()
+                .unwrap_or_else(|| RowContent::new(vec![]));
+            printer.event(event, row)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Record an applied item, returning the change if it is new or was modified.
+fn applied<K: Resource>(
+    known: &mut HashMap<String, String>,
+    item: K,
+) -> Option<(EventType, Arc<K>)> {
+    let version = item.resource_version().unwrap_or_default();
+    match known.insert(item.name_any(), version.clone()) {
+        None => Some((EventType::Added, Arc::new(item))),
+        Some(previous) if previous != version => Some((EventType::Modified, Arc::new(item))),
+        Some(_) => None,
+    }
+}
+
+struct Printer {
+    format: OutputFormat,
+    header: Vec<String>,
+    header_written: bool,
+}
+
+impl Printer {
+    fn new(format: OutputFormat, header: Vec<String>) -> Self {
+        Self {
+            format,
+            header,
+            header_written: false,
+        }
+    }
+
+    /// Print all rows at once.
+    fn table(&mut self, rows: Vec<RowContent>) -> anyhow::Result<()> {
+        let mut out = stdout().lock();
+
+        match self.format {
+            OutputFormat::Plain => {
+                let mut widths: Vec<usize> = self.header.iter().map(|h| h.len()).collect();
+                for row in &rows {
+                    for (width, cell) in widths.iter_mut().zip(&row.cells) {
+                        *width = (*width).max(cell.chars().count());
+                    }
+                }
+
+                writeln!(out, "{}", align(&self.header, &widths))?;
+                for row in &rows {
+                    writeln!(out, "{}", align(&row.cells, &widths))?;
+                }
+            }
+            OutputFormat::Json => {
+                let items = rows
+                    .iter()
+                    .map(|row| Value::Object(self.object(&row.cells)))
+                    .collect::<Vec<_>>();
+                serde_json::to_writer_pretty(&mut out, &items)?;
+                writeln!(out)?;
+            }
+            OutputFormat::Csv => {
+                let mut writer = csv::Writer::from_writer(out);
+                writer.write_record(&self.header)?;
+                for row in &rows {
+                    writer.write_record(&row.cells)?;
+                }
+                writer.flush()?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Print a single change, as one line.
+    fn event(&mut self, event: EventType, row: RowContent) -> anyhow::Result<()> {
+        let mut out = stdout().lock();
+
+        match self.format {
+            OutputFormat::Plain => {
+                writeln!(out, "{:<8}  {}", event.as_str(), row.cells.join("  "))?;
+            }
+            OutputFormat::Json => {
+                let mut object = Map::new();
+                object.insert("event".into(), event.as_str().into());
+                object.insert("object".into(), Value::Object(self.object(&row.cells)));
+                serde_json::to_writer(&mut out, &object)?;
+                writeln!(out)?;
+            }
+            OutputFormat::Csv => {
+                let mut writer = csv::Writer::from_writer(&mut out);
+                if !self.header_written {
+                    writer.write_record(
+                        ["Event"]
+                            .into_iter()
+                            .chain(self.header.iter().map(String::as_str)),
+                    )?;
+                    self.header_written = true;
+                }
+                writer.write_record(
+                    [event.as_str()]
+                        .into_iter()
+                        .chain(row.cells.iter().map(String::as_str)),
+                )?;
+                writer.flush()?;
+            }
+        }
+
+        out.flush()?;
+        Ok(())
+    }
+
+    /// Convert a row into an object, using the column names as keys.
+    fn object(&self, cells: &[String]) -> Map<String, Value> {
+        self.header
+            .iter()
+            .zip(cells)
+            .map(|(name, value)| (name.to_lowercase().replace(' ', "_"), value.clone().into()))
+            .collect()
+    }
+}
+
+fn align(cells: &[String], widths: &[usize]) -> String {
+    cells
+        .iter()
+        .zip(widths)
+        .map(|(cell, width)| format!("{cell:<width$}"))
+        .collect::<Vec<_>>()
+        .join("   ")
+        .trim_end()
+        .to_string()
+}
@@ -0,0 +1,318 @@
+//! Wait for conditions, for deployment pipelines.
+
+use crate::app::state::{make_ready, pod_state, Severity};
+use crate::client::Client;
+use crate::k8s::Reflector;
+use crate::Args;
+use anyhow::bail;
+use futures::{
+    stream::{select_all, BoxStream},
+    Stream, StreamExt,
+};
+use humantime::format_duration;
+use k8s_openapi::api::{apps::v1::Deployment, core::v1::Pod};
+use kube::{
+    runtime::{watcher, WatchStreamExt},
+    ResourceExt,
+};
+use std::{
+    collections::HashSet,
+    fmt::{self, Display, Formatter},
+    sync::Arc,
+    time::Duration,
+};
+
+/// Wait until conditions hold, or fail after a timeout
+#[derive(clap::Args, Debug, Clone)]
+pub struct WaitArgs {
+    /// The conditions to wait for, all of them must hold at the same time
+    #[clap(
+        long = "for",
+        value_enum,
+        value_delimiter = ',',
+        default_value = "ready"
+    )]
+    pub conditions: Vec<Condition>,
+    /// Fail if the conditions don't hold after this duration (e.g. 5m)
+    #[clap(long, value_parser = humantime::parse_duration, default_value = "5m")]
+    pub timeout: Duration,
+    /// Only consider objects matching this label selector
+    #[clap(short = 'l', long)]
+    pub selector: Option<String>,
+}
+
+/// A condition to wait for
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum Condition {
+    /// All pods are ready (or completed)
+    Ready,
+    /// The rollout of all deployments is complete
+    Rollout,
+    /// No pod is in CrashLoopBackOff
+    NoCrashLoop,
+}
+
+impl Condition {
+    fn needs_pods(&self) -> bool {
+        matches!(self, Self::Ready | Self::NoCrashLoop)
+    }
+
+    fn needs_deployments(&self) -> bool {
+        matches!(self, Self::Rollout)
+    }
+}
+
+/// An object violating a condition.
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Violation {
+    /// The condition may still hold later on
+    Pending(String),
+    /// The condition won't hold without intervention, like a rollout exceeding its deadline
+    Failed(String),
+}
+
+impl Display for Violation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Pending(reason) | Self::Failed(reason) => f.write_str(reason),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+enum Source {
+    Pods,
+    Deployments,
+}
+
+/// Watch pods and deployments, until all conditions hold.
+pub async fn run(args: &Args, wait: &WaitArgs) -> anyhow::Result<()> {
+    run_with(&Client::new(args.clone()), wait).await
+}
+
+/// Watch pods and deployments using the provided client, until all conditions hold.
+pub async fn run_with(client: &Client, wait: &WaitArgs) -> anyhow::Result<()> {
+    let config = watcher::Config {
+        label_selector: wait.selector.clone(),
+        ..Default::default()
+    };
+
+    // we only care about when a source has been listed initially, the stores hold the content
+    let mut expected = HashSet::new();
+    let mut streams = vec![];
+
+    let pods = match wait.conditions.iter().any(Condition::needs_pods) {
+        true => {
+            let reflector = Reflector::<Pod>::with_config(client, config.clone()).await?;
+            expected.insert(Source::Pods);
+            streams.push(watch(Source::Pods, reflector.stream));
+            Some(reflector.reader)
+        }
+        false => None,
+    };
+    let deployments = match wait.conditions.iter().any(Condition::needs_deployments) {
+        true => {
+            let reflector = Reflector::<Deployment>::with_config(client, config).await?;
+            expected.insert(Source::Deployments);
+            streams.push(watch(Source::Deployments, reflector.stream));
+            Some(reflector.reader)
+        }
+        false => None,
+    };
+
+    let mut events = select_all(streams);
+
+    let mut synced = HashSet::new();
+    let mut offending = vec![];
+
+    let timeout = tokio::time::sleep(wait.timeout);
+    tokio::pin!(timeout);
+
+    loop {
+        tokio::select! {
+            _ = &mut timeout => {
+                for offender in &offending {
+                    eprintln!("{offender}");
+                }
+                bail!("Timed out after {}", format_duration(wait.timeout));
+            }
+            event = events.next() => match event {
+                Some((source, Ok(true))) => {
+                    synced.insert(source);
+                }
+                Some((_, Ok(false))) => {}
+                Some((_, Err(err))) => eprintln!("Watch error: {err}"),
+                None => bail!("Watch closed"),
+            }
+        }
+
+        if synced != expected {
+            continue;
+        }
+
+        let pods = pods.as_ref().map(|r| r.state()).unwrap_or_default();
+        let deployments = deployments.as_ref().map(|r| r.state()).unwrap_or_default();
+
+        let current = wait
+            .conditions
+            .iter()
+            .flat_map(|condition| check(*condition, &pods, &deployments))
+            .collect::<Vec<_>>();
+
+        if current.is_empty() {
+            println!("All conditions met");
+            return Ok(());
+        }
+
+        // no point in waiting for the timeout, like `kubectl rollout status`
+        let failed: Vec<_> = current
+            .iter()
+            .filter(|violation| matches!(violation, Violation::Failed(_)))
+            .collect();
+        if !failed.is_empty() {
+            for violation in &failed {
+                eprintln!("{violation}");
+            }
+            bail!("{} object(s) failed", failed.len());
+        }
+
+        if current != offending {
+            eprintln!("Waiting for {} object(s)", current.len());
+            offending = current;
+        }
+    }
+}
+
+/// A stream of watch events, reporting `true` when a source was (re-)listed.
+fn watch<K>(
+    source: Source,
+    stream: impl Stream<Item = watcher::Result<watcher::Event<K>>> + Send + 'static,
+) -> BoxStream<'static, (Source, watcher::Result<bool>)>
+where
+    K: Send + 'static,
+{
+    stream
+        .default_backoff()
+        .map(move |event| {
+            let listed = event.map(|event| matches!(event, watcher::Event::Restarted(_)));
+            (source, listed)
+        })
+        .boxed()
+}
+
+/// Check a condition, returning all objects violating it.
+fn check(
+    condition: Condition,
+    pods: &[Arc<Pod>],
+    deployments: &[Arc<Deployment>],
+) -> Vec<Violation> {
+    match condition {
+        Condition::Ready if pods.is_empty() => {
+            vec![Violation::Pending("No pods found".to_string())]
+        }
+        Condition::Ready => pods
+            .iter()
+            .filter(|pod| !is_ready(pod))
+            .map(|pod| Violation::Pending(describe_pod(pod, "not ready")))
+            .collect(),
+        Condition::NoCrashLoop => pods
+            .iter()
+            .filter(|pod| pod_state(pod).severity() == Severity::Critical)
+            .map(|pod| Violation::Pending(describe_pod(pod, "crash looping")))
+            .collect(),
+        Condition::Rollout if deployments.is_empty() => {
+            vec![Violation::Pending("No deployments found".to_string())]
+        }
+        Condition::Rollout => deployments
+            .iter()
+            .filter_map(|deployment| {
+                let describe = |reason| format!("deployment/{}: {reason}", deployment.name_any());
+                match rollout_status(deployment) {
+                    Rollout::Complete => None,
+                    Rollout::Pending(reason) => Some(Violation::Pending(describe(reason))),
+                    Rollout::Failed(reason) => Some(Violation::Failed(describe(reason))),
+                }
+            })
+            .collect(),
+    }
+}
+
+fn describe_pod(pod: &Pod, problem: &str) -> String {
+    let ready = pod.status.as_ref().and_then(make_ready).unwrap_or_default();
+    format!(
+        "pod/{}: {problem} ({ready} {})",
+        pod.name_any(),
+        pod_state(pod)
+    )
+}
+
+/// A pod is ready if all of its containers are, completed pods are fine too.
+fn is_ready(pod: &Pod) -> bool {
+    let Some(status) = &pod.status else {
+        return false;
+    };
+    if status.phase.as_deref() == Some("Succeeded") {
+        return true;
+    }
+    if pod.metadata.deletion_timestamp.is_some() {
+        return false;
+    }
+
+    match &status.container_statuses {
+        Some(containers) if !containers.is_empty() => containers.iter().all(|c| c.ready),
+        _ => false,
+    }
+}
+
+/// The state of the rollout of a deployment.
+enum Rollout {
+    Complete,
+    Pending(String),
+    Failed(String),
+}
+
+/// Check the rollout of a deployment, like `kubectl rollout status`.
+fn rollout_status(deployment: &Deployment) -> Rollout {
+    let Some(status) = &deployment.status else {
+        return Rollout::Pending("no status yet".into());
+    };
+
+    if deployment.metadata.generation > status.observed_generation {
+        return Rollout::Pending("waiting for the rollout to be observed".into());
+    }
+
+    if let Some(condition) = status
+        .conditions
+        .iter()
+        .flatten()
+        .find(|c| c.type_ == "Progressing")
+    {
+        if condition.reason.as_deref() == Some("ProgressDeadlineExceeded") {
+            return Rollout::Failed("progress deadline exceeded".into());
+        }
+    }
+
+    let desired = deployment
+        .spec
+        .as_ref()
+        .and_then(|spec| spec.replicas)
+        .unwrap_or(1);
+    let replicas = status.replicas.unwrap_or_default();
+    let updated = status.updated_replicas.unwrap_or_default();
+    let available = status.available_replicas.unwrap_or_default();
+
+    if updated < desired {
+        Rollout::Pending(format!("{updated} of {desired} replicas updated"))
+    } else if replicas > updated {
+        Rollout::Pending(format!(
+            "{} old replicas pending termination",
+            replicas - updated
+        ))
+    } else if available < updated {
+        Rollout::Pending(format!(
+            "{available} of {updated} updated replicas available"
+        ))
+    } else {
+        Rollout::Complete
+    }
+}
@@ -0,0 +1,1205 @@
+use chrono::{Duration, SecondsFormat, Utc};
+use clap::Parser;
+use hyper::Method;
+use k8s_openapi::api::{
+    apps::v1::Deployment,
+    core::v1::{Event, Pod},
+};
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::Time;
+use podium::{
+    app::{
+        edit::Edit,
+        state::{tabs::TabConfig, View},
+        ui, App,
+    },
+    client::Client,
+    config::Config,
+    input::{
+        key::Key,
+        keymap::{Action, KeyBindings, Scope},
+        mouse::{Mouse, MouseKind},
+    },
+    k8s::fake::FakeApi,
+    Args,
+};
+use ratatui::{backend::TestBackend, Terminal};
+use serde_json::json;
+use std::{collections::BTreeMap, time::Duration as StdDuration};
+
+const WIDTH: u16 = 64;
+const HEIGHT: u16 = 10;
+
+fn created(minutes: i64) -> String {
+    (Utc::now() - Duration::minutes(minutes)).to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+fn pod(name: &str, ready: bool, waiting: Option<&str>, restarts: i32) -> Pod {
+    let state = match waiting {
+        Some(reason) => json!({ "waiting": { "reason": reason } }),
+        None => json!({ "running": { "startedAt": created(10) } }),
+    };
+
+    serde_json::from_value(json!({
+        "metadata": {
+            "name": name,
+            "namespace": "default",
+            "creationTimestamp": created(10),
+        },
+        "status": {
+            "phase": "Running",
+            "containerStatuses": [{
+                "name": "app",
+                "image": "app:latest",
+                "imageID": "",
+                "ready": ready,
+                "restartCount": restarts,
+                "state": state,
+            }],
+        },
+    }))
+    .unwrap()
+}
+
+fn deployment(name: &str, replicas: i32, ready: i32) -> Deployment {
+    serde_json::from_value(json!({
+        "metadata": {
+            "name": name,
+            "namespace": "default",
+            "creationTimestamp": created(180),
+        },
+        "spec": {
+            "replicas": replicas,
+            "selector": { "matchLabels": { "app": name } },
+            "template": {},
+        },
+        "status": {
+            "replicas": replicas,
+            "readyReplicas": ready,
+            "updatedReplicas": replicas,
+            "availableReplicas": ready,
+        },
+    }))
+    .unwrap()
+}
+
+struct Harness {
+    api: FakeApi,
+    app: App,
+    terminal: Terminal<TestBackend>,
+}
+
+impl Harness {
+    fn new(api: FakeApi, view: View) -> Self {
+        Self::with_config(api, view, &Config::default())
+    }
+
+    fn with_config(api: FakeApi, view: View, config: &Config) -> Self {
+        let mut args = Args::parse_from(["podium"]);
+        args.view = Some(view);
+        let client = Client::with_client(args.clone(), api.client());
+        let app = App::with_client(args, config, client).unwrap();
+        let terminal = Terminal::new(TestBackend::new(WIDTH, HEIGHT)).unwrap();
+
+        Self { api, app, terminal }
+    }
+
+    fn render(&mut self) -> Vec<String> {
+        self.app.poll();
+        let app = &self.app;
+        let frame = self.terminal.draw(|f| ui::draw(f, app)).unwrap();
+        let buffer = frame.buffer;
+
+        (0..buffer.area.height)
+            .map(|y| {
+                (0..buffer.area.width)
+                    .map(|x| buffer.get(x, y).symbol())
+                    .collect::<String>()
+                    .trim_end()
+                    .to_string()
+            })
+            .collect()
+    }
+
+    /// Render until the output matches, as the watchers run in the background.
+    async fn expect(&mut self, expected: &[&str]) {
+        let mut actual = vec![];
+        for _ in 0..100 {
+            actual = self.render();
+            if actual == expected {
+                return;
+            }
+            tokio::time::sleep(StdDuration::from_millis(20)).await;
+        }
+
+        assert_eq!(actual.join("\n"), expected.join("\n"));
+    }
+
+    /// Render until the output passes the check.
+    async fn wait_for(&mut self, check: impl Fn(&[String]) -> bool) {
+        let mut actual = vec![];
+        for _ in 0..200 {
+            actual = self.render();
+            if check(&actual) {
+                return;
+            }
+            tokio::time::sleep(StdDuration::from_millis(20)).await;
+        }
+
+        panic!("Unexpected output:\n{}", actual.join("\n"));
+    }
+
+    async fn key(&mut self, key: Key) {
+        self.app.do_action(key).await;
+    }
+
+    async fn mouse(&mut self, kind: MouseKind, column: u16, row: u16) {
+        self.app.on_mouse(Mouse::new(kind, column, row)).await;
+    }
+}
+
+#[tokio::test]
+async fn pods_table() {
+    let api = FakeApi::new();
+    api.apply(&pod("backend", true, None, 0));
+    api.apply(&pod("frontend", false, Some("CrashLoopBackOff"), 3));
+    api.apply(&pod("worker", false, Some("ContainerCreating"), 0));
+
+    let mut harness = Harness::new(api, View::Pods);
+    harness
+        .expect(&[
+            " 1 Overview  2 Pods 3 1!  3 Deployments  4 Events  5 ConfigMaps",
+            "┌Pods──────────────────────────────────────────────────────────┐",
+            "│   Name     Ready State             Restarts Age              │",
+            "│>> frontend 0/1   CrashLoopBackOff  3        10m              │",
+            "│   worker   0/1   ContainerCreating 0        10m              │",
+            "│   backend  1/1   Running           0        10m              │",
+            "│                                                              │",
+            "└──────────────────────────────────────────────────────────────┘",
+            " ns: default │ v1.29.0-fake │ watches: Pod ✓ │ 3 items",
+            " <Ctrl+k> kill  <i> details  <Delete> delete  <q> quit  <h> help",
+        ])
+        .await;
+}
+
+#[tokio::test]
+async fn deployments_table() {
+    let api = FakeApi::new();
+    api.apply(&deployment("backend", 3, 3));
+    api.apply(&deployment("frontend", 2, 1));
+
+    let mut harness = Harness::new(api, View::Deployments);
+    harness
+        .expect(&[
+            " 1 Overview  2 Pods  3 Deployments 2 1!  4 Events  5 ConfigMaps",
+            "┌Deployments───────────────────────────────────────────────────┐",
+            "│   Name     Ready Updated Available Age                       │",
+            "│>> backend  3/3   3       3         3h                        │",
+            "│   frontend 1/2   2       1         3h                        │",
+            "│                                                              │",
+            "│                                                              │",
+            "└──────────────────────────────────────────────────────────────┘",
+            " ns: default │ v1.29.0-fake │ watches: Deployment ✓ │ 2 items",
+            " <r> restart  <+> scale up  <-> scale down  <Delete> delete  <q>",
+        ])
+        .await;
+}
+
+#[tokio::test]
+async fn pod_updates_are_shown() {
+    let api = FakeApi::new();
+    api.apply(&pod("backend", false, Some("ContainerCreating"), 0));
+
+    let mut harness = Harness::new(api.clone(), View::Pods);
+    harness
+        .expect(&[
+            " 1 Overview  2 Pods 1  3 Deployments  4 Events  5 ConfigMaps  6",
+            "┌Pods──────────────────────────────────────────────────────────┐",
+            "│   Name    Ready State             Restarts Age               │",
+            "│>> backend 0/1   ContainerCreating 0        10m               │",
+            "│                                                              │",
+            "│                                                              │",
+            "│                                                              │",
+            "└──────────────────────────────────────────────────────────────┘",
+            " ns: default │ v1.29.0-fake │ watches: Pod ✓ │ 1 item",
+            " <Ctrl+k> kill  <i> details  <Delete> delete  <q> quit  <h> help",
+        ])
+        .await;
+
+    api.apply(&pod("backend", true, None, 0));
+    api.apply(&pod("frontend", true, None, 1));
+
+    harness
+        .expect(&[
+            " 1 Overview  2 Pods 2  3 Deployments  4 Events  5 ConfigMaps  6",
+            "┌Pods──────────────────────────────────────────────────────────┐",
+            "│   Name     Ready State   Restarts Age                        │",
+            "│>> backend  1/1   Running 0        10m                        │",
+            "│   frontend 1/1   Running 1        10m                        │",
+            "│                                                              │",
+            "│                                                              │",
+            "└──────────────────────────────────────────────────────────────┘",
+            " ns: default │ v1.29.0-fake │ watches: Pod ✓ │ 2 items",
+            " <Ctrl+k> kill  <i> details  <Delete> delete  <q> quit  <h> help",
+        ])
+        .await;
+}
+
+#[tokio::test]
+async fn navigate_and_kill_pod() {
+    let api = FakeApi::new();
+    api.apply(&pod("backend", true, None, 0));
+    api.apply(&pod("frontend", true, None, 0));
+
+    let mut harness = Harness::new(api.clone(), View::Pods);
+    harness
+        .expect(&[
+            " 1 Overview  2 Pods 2  3 Deployments  4 Events  5 ConfigMaps  6",
+            "┌Pods──────────────────────────────────────────────────────────┐",
+            "│   Name     Ready State   Restarts Age                        │",
+            "│>> backend  1/1   Running 0        10m                        │",
+            "│   frontend 1/1   Running 0        10m                        │",
+            "│                                                              │",
+            "│                                                              │",
+            "└──────────────────────────────────────────────────────────────┘",
+            " ns: default │ v1.29.0-fake │ watches: Pod ✓ │ 2 items",
+            " <Ctrl+k> kill  <i> details  <Delete> delete  <q> quit  <h> help",
+        ])
+        .await;
+
+    harness.key(Key::Char('j')).await;
+    harness
+        .expect(&[
+            " 1 Overview  2 Pods 2  3 Deployments  4 Events  5 ConfigMaps  6",
+            "┌Pods──────────────────────────────────────────────────────────┐",
+            "│   Name     Ready State   Restarts Age                        │",
+            "│   backend  1/1   Running 0        10m                        │",
+            "│>> frontend 1/1   Running 0        10m                        │",
+            "│                                                              │",
+            "│                                                              │",
+            "└──────────────────────────────────────────────────────────────┘",
+            " ns: default │ v1.29.0-fake │ watches: Pod ✓ │ 2 items",
+            " <Ctrl+k> kill  <i> details  <Delete> delete  <q> quit  <h> help",
+        ])
+        .await;
+
+    harness.key(Key::Ctrl('k')).await;
+    harness
+        .expect(&[
+            " 1 Overview  2 Pods 2  3 Deployments  4 Events  5 ConfigMaps  6",
+            "┌Pods────────┌Kill frontend?──────────────────────┐────────────┐",
+            "│   Name     │ <m> Mode         delete            │            │",
+            "│   backend  │ <g> Grace period default           │            │",
+            "│>> frontend │ <p> Propagation  default           │            │",
+            "│            │                                    │            │",
+            "│            │ frontend                           │            │",
+            "└────────────└────────────────────────────────────┘────────────┘",
+            " ns: default │ v1.29.0-fake │ watches: Pod ✓ │ 2 items",
+            " <y> confirm  <n> cancel  <Ctrl+c> quit",
+        ])
+        .await;
+    harness.key(Key::Char('y')).await;
+    harness
+        .expect(&[
+            " 1 Overview  2 Pods 1  3 Deployments  4 Events  5 ConfigMaps  6",
+            "┌Pods──────────────────────────────────────────────────────────┐",
+            "│   Name    Ready State   Restarts Age                         │",
+            "│>> backend 1/1   Running 0        10m                         │",
+            "│                                                              │",
+            "│                                                              │",
+            "│                                                              │",
+            "└──────────────────────────────────────────────────────────────┘",
+            " ns: default │ v1.29.0-fake │ watches: Pod ✓ │ 1 item",
+            " <Ctrl+k> kill  <i> details  <Delete> delete  <q> quit  <h> help",
+        ])
+        .await;
+
+    let deletes = harness
+        .api
+        .requests()
+        .into_iter()
+        .filter(|r| r.method == Method::DELETE)
+        .collect::<Vec<_>>();
+    assert_eq!(deletes.len(), 1);
+    assert_eq!(deletes[0].path, "/api/v1/namespaces/default/pods/frontend");
+    assert!(api.get::<Pod>("default", "frontend").is_none());
+}
+
+#[tokio::test]
+async fn scale_deployment() {
+    let api = FakeApi::new();
+    api.apply(&deployment("backend", 2, 2));
+
+    let mut harness = Harness::new(api.clone(), View::Deployments);
+    harness
+        .expect(&[
+            " 1 Overview  2 Pods  3 Deployments 1  4 Events  5 ConfigMaps  6",
+            "┌Deployments───────────────────────────────────────────────────┐",
+            "│   Name    Ready Updated Available Age                        │",
+            "│>> backend 2/2   2       2         3h                         │",
+            "│                                                              │",
+            "│                                                              │",
+            "│                                                              │",
+            "└──────────────────────────────────────────────────────────────┘",
+            " ns: default │ v1.29.0-fake │ watches: Deployment ✓ │ 1 item",
+            " <r> restart  <+> scale up  <-> scale down  <Delete> delete  <q>",
+        ])
+        .await;
+
+    harness.key(Key::Char('+')).await;
+
+    for _ in 0..100 {
+        let replicas = api
+            .get::<Deployment>("default", "backend")
+            .and_then(|d| d.spec)
+            .and_then(|s| s.replicas);
+        if replicas == Some(3) {
+            return;
+        }
+        tokio::time::sleep(StdDuration::from_millis(20)).await;
+    }
+    panic!("Deployment was not scaled");
+}
+
+#[tokio::test]
+async fn long_confirmation_keeps_options() {
+    let api = FakeApi::new();
+    for name in ["a", "b", "c", "d", "e", "f"] {
+        api.apply(&deployment(name, 1, 1));
+    }
+
+    let mut harness = Harness::new(api.clone(), View::Deployments);
+    harness.wait_for(|lines| lines[3].contains(">> a")).await;
+
+    harness.key(Key::Char('*')).await;
+    harness.key(Key::Delete).await;
+    // the options and the warning come first, the objects are shortened to the space left
+    harness
+        .expect(&[
+            " 1 Overview  ┌Delete 6 deployments?───────────────┐onfigMaps  6",
+            "┌Deployments │ <g> Grace period default           │────────────┐",
+            "│   Name Read│ <p> Propagation  default           │            │",
+            "│>> a    1/1 │                                    │            │",
+            "│   b    1/1 │ ⚠ This also deletes 6 pods         │            │",
+            "│   c    1/1 │                                    │            │",
+            "│   d    1/1 │ a                                  │            │",
+            "└────────────│ b                                  │────────────┘",
+            " ns: default │ … and 4 more                       │ │ 6 items",
+            " <y> confirm └────────────────────────────────────┘",
+        ])
+        .await;
+}
+
+#[tokio::test]
+async fn delete_deployment() {
+    let api = FakeApi::new();
+    api.apply(&deployment("backend", 2, 2));
+    api.apply(&deployment("frontend", 3, 3));
+
+    let mut harness = Harness::new(api.clone(), View::Deployments);
+    harness.terminal.backend_mut().resize(WIDTH, 14);
+    harness
+        .wait_for(|lines| lines[3].contains(">> backend"))
+        .await;
+
+    harness.key(Key::Char('*')).await;
+    harness.key(Key::Delete).await;
+    harness
+        .expect(&[
+            " 1 Overview  2 Pods  3 Deployments 2  4 Events  5 ConfigMaps  6",
+            "┌Deployments (2 marked)────────────────────────────────────────┐",
+            "│   Name     ┌Delete 2 deployments?───────────────┐            │",
+            "│>> backend  │ <g> Grace period default           │            │",
+            "│   frontend │ <p> Propagation  default           │            │",
+            "│            │                                    │            │",
+            "│            │ ⚠ This also deletes 5 pods         │            │",
+            "│            │                                    │            │",
+            "│            │ backend                            │            │",
+            "│            │ frontend                           │            │",
+            "│            └────────────────────────────────────┘            │",
+            "└──────────────────────────────────────────────────────────────┘",
+            " ns: default │ v1.29.0-fake │ watches: Deployment ✓ │ 2 items",
+            " <y> confirm  <n> cancel  <Ctrl+c> quit",
+        ])
+        .await;
+
+    // orphaned pods are kept, so there is nothing to warn about
+    for _ in 0..3 {
+        harness.key(Key::Char('p')).await;
+    }
+    harness
+        .wait_for(|lines| {
+            lines
+                .iter()
+                .any(|line| line.contains("<p> Propagation  orphan "))
+                && !lines.iter().any(|line| line.contains("This also deletes"))
+        })
+        .await;
+
+    harness.key(Key::Char('y')).await;
+    harness
+        .wait_for(|lines| {
+            lines.iter().any(|line| line.contains("│ ✓ backend"))
+                && lines.iter().any(|line| line.contains("│ ✓ frontend"))
+        })
+        .await;
+
+    let deletes = api
+        .requests()
+        .into_iter()
+        .filter(|r| r.method == Method::DELETE)
+        .collect::<Vec<_>>();
+    assert_eq!(deletes.len(), 2);
+    assert_eq!(
+        deletes[0].path,
+        "/apis/apps/v1/namespaces/default/deployments/backend"
+    );
+    let body = deletes[0].body.clone().unwrap();
+    assert_eq!(body["propagationPolicy"], "Orphan");
+    assert!(body["preconditions"]["uid"].is_string());
+    assert!(body["preconditions"]["resourceVersion"].is_string());
+    assert!(api.get::<Deployment>("default", "frontend").is_none());
+}
+
+#[tokio::test]
+async fn unschedulable_pod() {
+    let api = FakeApi::new();
+    api.apply(
+        &serde_json::from_value::<Pod>(json!({
+            "metadata": {
+                "name": "backend",
+                "namespace": "default",
+                "creationTimestamp": created(10),
+            },
+            "status": {
+                "phase": "Pending",
+                "conditions": [{
+                    "type": "PodScheduled",
+                    "status": "False",
+                    "reason": "Unschedulable",
+                    "message": "0/2 nodes are available: 2 Insufficient cpu.",
+                }],
+            },
+        }))
+        .unwrap(),
+    );
+
+    let mut harness = Harness::new(api, View::Pods);
+    harness.terminal.backend_mut().resize(WIDTH, 20);
+    harness
+        .expect(&[
+            " 1 Overview  2 Pods 1 1!  3 Deployments  4 Events  5 ConfigMaps",
+            "┌Pods──────────────────────────────────────────────────────────┐",
+            "│   Name    Ready State         Restarts Age                   │",
+            "│>> backend       Unschedulable 0        10m                   │",
+            "│                                                              │",
+            "│                                                              │",
+            "│                                                              │",
+            "│                                                              │",
+            "│                                                              │",
+            "│                                                              │",
+            "│                                                              │",
+            "│                                                              │",
+            "│                                                              │",
+            "│                                                              │",
+            "│                                                              │",
+            "│                                                              │",
+            "└──────────────────────────────────────────────────────────────┘",
+            " Unschedulable: 0/2 nodes are available: 2 Insufficient cpu.",
+            " ns: default │ v1.29.0-fake │ watches: Pod ✓ │ 1 item",
+            " <Ctrl+k> kill  <i> details  <Delete> delete  <q> quit  <h> help",
+        ])
+        .await;
+
+    harness.key(Key::Char('i')).await;
+    harness
+        .expect(&[
+            " 1 Overview  2 Pods 1 1!  3 Deployments  4 Events  5 ConfigMaps",
+            "┌Pods──────────────────────────────────────────────────────────┐",
+            "│   Name    Ready State         Restarts Age                   │",
+            "│>> backend       Unschedulable 0        10m                   │",
+            "│                                                              │",
+            "│                                                              │",
+            "│                                                              │",
+            "└──────────────────────────────────────────────────────────────┘",
+            " Unschedulable: 0/2 nodes are available: 2 Insufficient cpu.",
+            "┌Details───────────────────────────────────────────────────────┐",
+            "│Node: <none>                                                  │",
+            "│State: Unschedulable                                          │",
+            "│                                                              │",
+            "│Scheduling                                                    │",
+            "│  Unschedulable                                               │",
+            "│  0/2 nodes are available                                     │",
+            "│    - 2 Insufficient cpu                                      │",
+            "└──────────────────────────────────────────────────────────────┘",
+            " ns: default │ v1.29.0-fake │ watches: Pod ✓ │ 1 item",
+            " <Ctrl+k> kill  <i> details  <Delete> delete  <q> quit  <h> help",
+        ])
+        .await;
+}
+
+#[tokio::test]
+async fn overview_jumps_to_filtered_list() {
+    let api = FakeApi::new();
+    api.apply(&pod("backend", true, None, 0));
+    api.apply(&pod("frontend", false, Some("CrashLoopBackOff"), 3));
+    api.apply(&deployment("backend", 1, 1));
+    api.apply(&deployment("frontend", 2, 0));
+
+    let mut worker = pod("worker", true, None, 0);
+    worker.spec = serde_json::from_value(json!({
+        "containers": [{
+            "name": "app",
+            "resources": { "requests": { "cpu": "250m", "memory": "128Mi" } },
+        }],
+    }))
+    .unwrap();
+    api.apply(&worker);
+
+    api.apply(
+        &serde_json::from_value::<Event>(json!({
+            "metadata": { "name": "frontend.1", "namespace": "default" },
+            "involvedObject": { "kind": "Pod", "name": "frontend" },
+            "type": "Warning",
+            "reason": "BackOff",
+            "message": "Back-off restarting failed container",
+            "lastTimestamp": created(2),
+        }))
+        .unwrap(),
+    );
+
+    let mut harness = Harness::new(api, View::Overview);
+    harness.terminal.backend_mut().resize(WIDTH, 14);
+    harness
+        .expect(&[
+            " 1 Overview  2 Pods 3 1!  3 Deployments 2 1!  4 Events 1 1!  5 C",
+            "┏Pods (3)━━━━━━━━━━━┓┌Unavailable (1)─────┐┌Requests───────────┐",
+            "┃   1  CrashLoopBack┃│    0/2  frontend   ││CPU:     250m      │",
+            "┃   2  Running      ┃│                    ││Memory:  128Mi     │",
+            "┃                   ┃│                    ││Pods:    1         │",
+            "┃                   ┃│                    ││                   │",
+            "┗━━━━━━━━━━━━━━━━━━━┛└────────────────────┘└───────────────────┘",
+            "┌Recent warnings (1)─────────────────┐┌Recent restarts (0)─────┐",
+            "│  2m  BackOff  pod/frontend: Back-of││                        │",
+            "│                                    ││                        │",
+            "│                                    ││                        │",
+            "└────────────────────────────────────┘└────────────────────────┘",
+            " ns: default │ v1.29.0-fake │ watches: Deployment ✓ Event ✓ Pod",
+            " <Enter> open  <Delete> delete  <q> quit  <h> help  <y> manifest",
+        ])
+        .await;
+
+    // open the unavailable deployments
+    harness.key(Key::Down).await;
+    harness.key(Key::Enter).await;
+    harness.terminal.backend_mut().resize(WIDTH, HEIGHT);
+    harness
+        .expect(&[
+            " 1 Overview  2 Pods 3 1!  3 Deployments 2 1!  4 Events 1 1!  5 C",
+            "┌Deployments (not available)───────────────────────────────────┐",
+            "│   Name     Ready Updated Available Age                       │",
+            "│>> frontend 0/2   2       0         3h                        │",
+            "│                                                              │",
+            "│                                                              │",
+            "│                                                              │",
+            "└──────────────────────────────────────────────────────────────┘",
+            " ns: default │ v1.29.0-fake │ watches: Deployment ✓ Event ✓ Pod",
+            " <r> restart  <+> scale up  <-> scale down  <Delete> delete  <q>",
+        ])
+        .await;
+
+    // going back removes the filter
+    harness.key(Key::Esc).await;
+    harness
+        .expect(&[
+            " 1 Overview  2 Pods 3 1!  3 Deployments 2 1!  4 Events 1 1!  5 C",
+            "┌Deployments───────────────────────────────────────────────────┐",
+            "│   Name     Ready Updated Available Age                       │",
+            "│>> backend  1/1   1       1         3h                        │",
+            "│   frontend 0/2   2       0         3h                        │",
+            "│                                                              │",
+            "│                                                              │",
+            "└──────────────────────────────────────────────────────────────┘",
+            " ns: default │ v1.29.0-fake │ watches: Deployment ✓ Event ✓ Pod",
+            " <r> restart  <+> scale up  <-> scale down  <Delete> delete  <q>",
+        ])
+        .await;
+}
+
+#[tokio::test]
+async fn switching_views_keeps_watchers() {
+    let api = FakeApi::new();
+    api.apply(&pod("backend", true, None, 0));
+    api.apply(&pod("frontend", true, None, 0));
+    api.apply(&deployment("backend", 1, 1));
+
+    let pods = |api: &FakeApi| {
+        api.requests()
+            .into_iter()
+            .filter(|r| r.method == Method::GET && r.path == "/api/v1/namespaces/default/pods")
+            .count()
+    };
+
+    let mut harness = Harness::new(api.clone(), View::Pods);
+    harness
+        .expect(&[
+            " 1 Overview  2 Pods 2  3 Deployments  4 Events  5 ConfigMaps  6",
+            "┌Pods──────────────────────────────────────────────────────────┐",
+            "│   Name     Ready State   Restarts Age                        │",
+            "│>> backend  1/1   Running 0        10m                        │",
+            "│   frontend 1/1   Running 0        10m                        │",
+            "│                                                              │",
+            "│                                                              │",
+            "└──────────────────────────────────────────────────────────────┘",
+            " ns: default │ v1.29.0-fake │ watches: Pod ✓ │ 2 items",
+            " <Ctrl+k> kill  <i> details  <Delete> delete  <q> quit  <h> help",
+        ])
+        .await;
+
+    harness.key(Key::Char('j')).await;
+    let selected = [
+        " 1 Overview  2 Pods 2  3 Deployments  4 Events  5 ConfigMaps  6",
+        "┌Pods──────────────────────────────────────────────────────────┐",
+        "│   Name     Ready State   Restarts Age                        │",
+        "│   backend  1/1   Running 0        10m                        │",
+        "│>> frontend 1/1   Running 0        10m                        │",
+        "│                                                              │",
+        "│                                                              │",
+        "└──────────────────────────────────────────────────────────────┘",
+        " ns: default │ v1.29.0-fake │ watches: Pod ✓ │ 2 items",
+        " <Ctrl+k> kill  <i> details  <Delete> delete  <q> quit  <h> help",
+    ];
+    harness.expect(&selected).await;
+    let requests = pods(&api);
+
+    harness.key(Key::Char('d')).await;
+    harness.key(Key::Char('p')).await;
+
+    // shown right away, with the selection kept and without listing the pods again
+    // the status bar also shows the watch of the deployments now
+    assert_eq!(harness.render()[..8], selected[..8]);
+    assert_eq!(pods(&api), requests);
+}
+
+#[tokio::test]
+async fn idle_views_are_evicted() {
+    let api = FakeApi::new();
+    let config = Config {
+        idle_timeout: Some(StdDuration::ZERO),
+        ..Default::default()
+    };
+
+    let mut harness = Harness::with_config(api, View::Pods, &config);
+    let kinds = |harness: &Harness| {
+        harness
+            .app
+            .registry()
+            .status()
+            .into_iter()
+            .map(|s| (s.kind, s.users))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(kinds(&harness), [("Pod".to_string(), 1)]);
+
+    harness.key(Key::Char('d')).await;
+    assert_eq!(kinds(&harness), [("Deployment".to_string(), 1)]);
+
+    // the overview shares the watchers with the other views
+    harness.key(Key::Char('o')).await;
+    assert_eq!(
+        kinds(&harness),
+        [
+            ("Deployment".to_string(), 1),
+            ("Event".to_string(), 1),
+            ("Pod".to_string(), 1),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn stale_data_is_kept() {
+    let api = FakeApi::new();
+    api.apply(&pod("backend", true, None, 0));
+
+    let mut harness = Harness::new(api.clone(), View::Pods);
+    let pods = [
+        " 1 Overview  2 Pods 1  3 Deployments  4 Events  5 ConfigMaps  6",
+        "┌Pods──────────────────────────────────────────────────────────┐",
+        "│   Name    Ready State   Restarts Age                         │",
+        "│>> backend 1/1   Running 0        10m                         │",
+        "│                                                              │",
+        "│                                                              │",
+        "│                                                              │",
+        "└──────────────────────────────────────────────────────────────┘",
+        " ns: default │ v1.29.0-fake │ watches: Pod ✓ │ 1 item",
+        " <Ctrl+k> kill  <i> details  <Delete> delete  <q> quit  <h> help",
+    ];
+    harness.expect(&pods).await;
+
+    // the content is kept, marked as stale since the last event
+    tokio::time::sleep(StdDuration::from_millis(1100)).await;
+    api.set_offline(true);
+    harness
+        .wait_for(|lines| {
+            lines[..6] == pods[..6]
+                && lines[7].starts_with(" Connection lost, last updated")
+                && lines[7].contains("ago: ")
+                && lines[8].contains("watches: Pod ✗")
+        })
+        .await;
+    assert!(!harness.render()[7].contains("updated 0s ago"));
+
+    // and updated again, once the API server is back
+    api.set_offline(false);
+    harness.expect(&pods).await;
+}
+
+#[tokio::test]
+async fn unreachable_api_shows_error() {
+    let api = FakeApi::new();
+    api.apply(&pod("backend", true, None, 0));
+    api.set_offline(true);
+
+    // without any data, the error is shown instead of an empty list
+    let mut harness = Harness::new(api.clone(), View::Pods);
+    harness
+        .wait_for(|lines| {
+            lines[1].starts_with("┌Error")
+                && lines[2].starts_with("│failed to perform initial object list")
+                && lines[8].contains("watches: Pod ✗")
+        })
+        .await;
+
+    api.set_offline(false);
+    harness
+        .wait_for(|lines| lines[3].starts_with("│>> backend"))
+        .await;
+}
+
+#[tokio::test]
+async fn mouse_selects_and_sorts() {
+    let api = FakeApi::new();
+    api.apply(&pod("backend", true, None, 0));
+    api.apply(&pod("frontend", false, Some("CrashLoopBackOff"), 3));
+    api.apply(&pod("worker", false, Some("ContainerCreating"), 0));
+
+    let mut harness = Harness::new(api, View::Pods);
+    harness
+        .wait_for(|lines| lines[3].starts_with("│>> frontend"))
+        .await;
+
+    // clicking a header sorts by its column
+    harness.mouse(MouseKind::Click, 6, 2).await;
+    harness
+        .expect(&[
+            " 1 Overview  2 Pods 3 1!  3 Deployments  4 Events  5 ConfigMaps",
+            "┌Pods──────────────────────────────────────────────────────────┐",
+            "│   Name ▲   Ready State             Restarts Age              │",
+            "│>> backend  1/1   Running           0        10m              │",
+            "│   frontend 0/1   CrashLoopBackOff  3        10m              │",
+            "│   worker   0/1   ContainerCreating 0        10m              │",
+            "│                                                              │",
+            "└──────────────────────────────────────────────────────────────┘",
+            " ns: default │ v1.29.0-fake │ watches: Pod ✓ │ 3 items",
+            " <Ctrl+k> kill  <i> details  <Delete> delete  <q> quit  <h> help",
+        ])
+        .await;
+
+    // clicking a row selects it, scrolling moves the selection
+    harness.mouse(MouseKind::Click, 20, 5).await;
+    assert!(harness.render()[5].starts_with("│>> worker"));
+    harness.mouse(MouseKind::ScrollUp, 20, 5).await;
+    assert!(harness.render()[4].starts_with("│>> frontend"));
+    // rows without an item are ignored
+    harness.mouse(MouseKind::Click, 20, 6).await;
+    assert!(harness.render()[4].starts_with("│>> frontend"));
+
+    // clicking again reverses the order, and then goes back to the default order
+    harness.mouse(MouseKind::Click, 6, 2).await;
+    let lines = harness.render();
+    assert!(lines[2].starts_with("│   Name ▼"));
+    assert!(lines[3].starts_with("│   worker"));
+    harness.mouse(MouseKind::Click, 6, 2).await;
+    let lines = harness.render();
+    assert!(lines[2].starts_with("│   Name     Ready"));
+    assert!(lines[3].starts_with("│   frontend"));
+}
+
+#[tokio::test]
+async fn small_terminal_shows_warning() {
+    let mut harness = Harness::new(FakeApi::new(), View::Pods);
+    harness.terminal.backend_mut().resize(30, 5);
+
+    assert_eq!(
+        harness.render(),
+        [
+            "",
+            "",
+            "  Terminal too small (30x5),",
+            "      needs at least 40x8",
+            "",
+        ]
+    );
+}
+
+#[tokio::test]
+async fn tabs_switch_views() {
+    let api = FakeApi::new();
+    api.apply(&pod("backend", true, None, 0));
+    api.apply(&deployment("backend", 2, 1));
+
+    let config = Config {
+        tabs: TabConfig {
+            pinned: vec![View::Deployments, View::Pods],
+            hidden: vec![View::Overview, View::Events, View::ConfigMaps],
+        },
+        ..Default::default()
+    };
+    let mut harness = Harness::with_config(api, View::Deployments, &config);
+    harness
+        .wait_for(|lines| lines[0] == " 1 Deployments 1 1!  2 Pods  3 Secrets")
+        .await;
+
+    // the number keys switch to the tab at that position
+    harness.key(Key::Char('2')).await;
+    harness
+        .wait_for(|lines| {
+            lines[0] == " 1 Deployments 1 1!  2 Pods 1  3 Secrets" && lines[1].starts_with("┌Pods")
+        })
+        .await;
+
+    // and so does clicking a tab
+    harness.mouse(MouseKind::Click, 5, 0).await;
+    harness
+        .wait_for(|lines| lines[1].starts_with("┌Deployments"))
+        .await;
+
+    // the previous and next views follow the tabs
+    harness.key(Key::Left).await;
+    harness
+        .wait_for(|lines| lines[1].starts_with("┌Secrets"))
+        .await;
+}
+
+#[tokio::test]
+async fn viewer_hints_follow_keymap() {
+    let mut backend = pod("backend", true, None, 0);
+    let applied = r#"{"apiVersion":"v1","kind":"Pod","metadata":{"name":"backend"}}"#;
+    backend.metadata.annotations = Some(BTreeMap::from([(
+        "kubectl.kubernetes.io/last-applied-configuration".to_string(),
+        applied.to_string(),
+    )]));
+    let api = FakeApi::new();
+    api.apply(&backend);
+
+    let search = BTreeMap::from([(Action::Search, KeyBindings::One("ctrl-s".to_string()))]);
+    let config = Config {
+        keys: BTreeMap::from([(Scope::Manifest, search)]),
+        ..Default::default()
+    };
+    let mut harness = Harness::with_config(api, View::Pods, &config);
+    harness.wait_for(|lines| lines[3].contains("backend")).await;
+
+    harness.key(Key::Char('y')).await;
+    harness
+        .wait_for(|lines| {
+            lines[9] == " <Ctrl+s> search  <n> next match  <N> previous match  <f> YAML/J"
+        })
+        .await;
+
+    harness.key(Key::Esc).await;
+    harness.key(Key::Char('D')).await;
+    harness
+        .wait_for(|lines| {
+            lines[9].starts_with(" <n> next change  <N> previous change  <p> pruning")
+        })
+        .await;
+}
+
+#[tokio::test]
+async fn edit_failure_follows_keymap() {
+    let api = FakeApi::new();
+    api.apply(&pod("backend", true, None, 0));
+
+    let close = BTreeMap::from([(Action::Close, KeyBindings::One("x".to_string()))]);
+    let config = Config {
+        keys: BTreeMap::from([(Scope::EditFailure, close)]),
+        ..Default::default()
+    };
+    let mut harness = Harness::with_config(api, View::Pods, &config);
+    harness.terminal.backend_mut().resize(WIDTH, 14);
+    harness.wait_for(|lines| lines[3].contains("backend")).await;
+
+    let object = harness.app.state().selected_object().unwrap().unwrap();
+    let edit = Edit::new(object).unwrap();
+    harness
+        .app
+        .apply_edit(edit, Err(anyhow::anyhow!("Invalid manifest")))
+        .await;
+    harness
+        .wait_for(|lines| {
+            lines[5].contains("Invalid manifest") && lines[7].contains("<e> edit  <x> close")
+        })
+        .await;
+
+    // the default binding no longer applies
+    harness.key(Key::Esc).await;
+    harness
+        .wait_for(|lines| lines[4].contains("Failed to apply changes"))
+        .await;
+    harness.key(Key::Char('x')).await;
+    harness
+        .wait_for(|lines| !lines.iter().any(|line| line.contains("Failed to apply")))
+        .await;
+}
+
+#[tokio::test]
+async fn bulk_kill_pods() {
+    let api = FakeApi::new();
+    api.apply(&pod("backend", true, None, 0));
+    api.apply(&pod("frontend", true, None, 0));
+    api.apply(&pod("worker", true, None, 0));
+
+    let mut harness = Harness::new(api.clone(), View::Pods);
+    harness.terminal.backend_mut().resize(WIDTH, 14);
+    harness.wait_for(|lines| lines[5].contains("worker")).await;
+
+    // mark the first two pods, moving down with each mark
+    harness.key(Key::Char(' ')).await;
+    harness.key(Key::Char(' ')).await;
+    harness.key(Key::Ctrl('k')).await;
+    harness
+        .expect(&[
+            " 1 Overview  2 Pods 3  3 Deployments  4 Events  5 ConfigMaps  6",
+            "┌Pods (2 marked)───────────────────────────────────────────────┐",
+            "│   Name     Ready State   Restarts Age                        │",
+            "│   backend  ┌Kill 2 pods?────────────────────────┐            │",
+            "│   frontend │ <m> Mode         delete            │            │",
+            "│>> worker   │ <g> Grace period default           │            │",
+            "│            │ <p> Propagation  default           │            │",
+            "│            │                                    │            │",
+            "│            │ backend                            │            │",
+            "│            │ frontend                           │            │",
+            "│            └────────────────────────────────────┘            │",
+            "└──────────────────────────────────────────────────────────────┘",
+            " ns: default │ v1.29.0-fake │ watches: Pod ✓ │ 3 items",
+            " <y> confirm  <n> cancel  <Ctrl+c> quit",
+        ])
+        .await;
+
+    // one of them is gone in the meantime, which is reported as a failure
+    api.remove::<Pod>("default", "frontend");
+    harness.key(Key::Char('y')).await;
+    harness
+        .wait_for(|lines| {
+            let line = |text: &str| lines.iter().position(|l| l.contains(text));
+            line("│ ✓ backend").is_some() && line("│ ✓ backend") < line("│ ✗ frontend: ")
+        })
+        .await;
+
+    let deletes = api
+        .requests()
+        .into_iter()
+        .filter(|r| r.method == Method::DELETE)
+        .map(|r| r.path)
+        .collect::<Vec<_>>();
+    assert_eq!(
+        deletes,
+        [
+            "/api/v1/namespaces/default/pods/backend",
+            "/api/v1/namespaces/default/pods/frontend"
+        ]
+    );
+
+    // closing the report shows the remaining pod, no longer marked
+    harness.key(Key::Enter).await;
+    harness
+        .wait_for(|lines| lines[1].starts_with("┌Pods─") && lines[3].contains(">> worker"))
+        .await;
+}
+
+#[tokio::test]
+async fn refused_eviction_is_reported() {
+    let api = FakeApi::new();
+    api.apply(&pod("backend", true, None, 0));
+    api.set_evictions_blocked(true);
+
+    let mut harness = Harness::new(api.clone(), View::Pods);
+    harness
+        .wait_for(|lines| lines[3].contains(">> backend"))
+        .await;
+
+    harness.key(Key::Ctrl('k')).await;
+    harness.key(Key::Char('m')).await;
+    harness.key(Key::Char('y')).await;
+    harness
+        .wait_for(|lines| {
+            let shown = |text: &str| lines.iter().any(|l| l.contains(text));
+            shown("Kill backend") && shown("✗ backend: ApiError: Cannot evict")
+        })
+        .await;
+
+    harness.key(Key::Enter).await;
+    harness
+        .wait_for(|lines| !lines.iter().any(|l| l.contains("Kill backend")))
+        .await;
+    assert!(api.get::<Pod>("default", "backend").is_some());
+}
+
+#[tokio::test]
+async fn delete_dialog_options() {
+    let api = FakeApi::new();
+    api.apply(&pod("backend", true, None, 0));
+    let mut stuck = pod("stuck", true, None, 0);
+    stuck.metadata.deletion_timestamp = Some(Time(Utc::now()));
+    api.apply(&stuck);
+
+    let mut harness = Harness::new(api.clone(), View::Pods);
+    harness
+        .wait_for(|lines| lines[3].contains(">> stuck") && lines[4].contains("backend"))
+        .await;
+
+    // pods stuck in terminating get a hint, but force deleting them takes choosing it
+    harness.key(Key::Ctrl('k')).await;
+    harness
+        .wait_for(|lines| {
+            let shown = |text: &str| lines.iter().any(|l| l.contains(text));
+            shown("Kill stuck?")
+                && shown("<g> Grace period default")
+                && shown("Terminating already")
+        })
+        .await;
+    for _ in 0..4 {
+        harness.key(Key::Char('g')).await;
+    }
+    harness
+        .wait_for(|lines| {
+            let shown = |text: &str| lines.iter().any(|l| l.contains(text));
+            shown("<g> Grace period force (0s)") && !shown("Terminating already")
+        })
+        .await;
+    // a change of its status doesn't prevent the deletion
+    api.apply(&stuck);
+    harness.key(Key::Char('y')).await;
+    harness
+        .wait_for(|lines| lines[3].contains(">> backend"))
+        .await;
+
+    // others can be evicted, respecting disruption budgets
+    harness.key(Key::Ctrl('k')).await;
+    harness.key(Key::Char('m')).await;
+    harness.key(Key::Char('p')).await;
+    harness
+        .wait_for(|lines| {
+            let shown = |text: &str| lines.iter().any(|l| l.contains(text));
+            shown("<m> Mode         evict ")
+                && shown("<g> Grace period default ")
+                && shown("<p> Propagation  background ")
+        })
+        .await;
+    harness.key(Key::Char('y')).await;
+    harness
+        .wait_for(|lines| lines[0].starts_with(" 1 Overview  2 Pods 0 "))
+        .await;
+
+    let requests = api
+        .requests()
+        .into_iter()
+        .filter(|r| r.method == Method::DELETE || r.method == Method::POST)
+        .collect::<Vec<_>>();
+    assert_eq!(requests.len(), 2);
+    assert_eq!(requests[0].path, "/api/v1/namespaces/default/pods/stuck");
+    let body = requests[0].body.clone().unwrap();
+    assert_eq!(body["gracePeriodSeconds"], 0);
+    assert!(body["preconditions"]["uid"].is_string());
+    assert!(body["preconditions"].get("resourceVersion").is_none());
+
+    assert_eq!(
+        requests[1].path,
+        "/api/v1/namespaces/default/pods/backend/eviction"
+    );
+    let body = requests[1].body.clone().unwrap();
+    assert_eq!(body["kind"], "Eviction");
+    assert_eq!(body["deleteOptions"]["propagationPolicy"], "Background");
+    assert!(body["deleteOptions"].get("gracePeriodSeconds").is_none());
+}
+
+#[tokio::test]
+async fn edit_labels() {
+    let api = FakeApi::new();
+    let mut backend = pod("backend", true, None, 0);
+    backend.metadata.labels = Some(
+        [("app", "backend"), ("tier", "web")]
+            .into_iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect(),
+    );
+    api.apply(&backend);
+
+    let mut harness = Harness::new(api.clone(), View::Pods);
+    harness.terminal.backend_mut().resize(WIDTH, 14);
+    harness
+        .wait_for(|lines| lines[3].contains(">> backend"))
+        .await;
+
+    harness.key(Key::Char('L')).await;
+    harness.key(Key::Char('d')).await;
+    harness.key(Key::Char('a')).await;
+    for c in "debug=true".chars() {
+        harness.key(Key::Char(c)).await;
+    }
+    harness.key(Key::Enter).await;
+    harness
+        .expect(&[
+            " 1 Overview  2 Pods 1  3 Deployments  4 Events  5 ConfigMaps  6",
+            "┌Pods─┌Labels and annotations of backend─────────────────┐─────┐",
+            "│   Na│Labels                                            │     │",
+            "│>> ba│ - app    backend                                 │     │",
+            "│     │   tier   web                                     │     │",
+            "│     │ + debug  true                                    │     │",
+            "│     │Annotations                                       │     │",
+            "│     │                                                  │     │",
+            "│     │                                                  │     │",
+            "│     │                                                  │     │",
+            "│     │                                                  │     │",
+            "└─────│ <a> add label  <A> add annotation  <e> change  <d│─────┘",
+            " ns: d└──────────────────────────────────────────────────┘",
+            " <a> add label  <A> add annotation  <e> change  <d> remove  <y>",
+        ])
+        .await;
+
+    // changes made in the meantime are not overwritten, the object is loaded again
+    api.apply(&backend);
+    harness.key(Key::Char('y')).await;
+    harness
+        .wait_for(|lines| lines[10].contains("Changed on the server, reloaded it"))
+        .await;
+    assert!(!harness.render().iter().any(|line| line.contains("debug")));
+
+    // and can be edited right away, applying the changes in the background
+    harness.key(Key::Char('j')).await;
+    harness.key(Key::Char('d')).await;
+    harness.key(Key::Char('y')).await;
+    let editor = harness.app.global().labels.as_ref().unwrap();
+    assert!(editor.is_applying());
+    harness
+        .wait_for(|lines| lines[1].starts_with("┌Pods──"))
+        .await;
+
+    let patches = api
+        .requests()
+        .into_iter()
+        .filter(|r| r.method == Method::PATCH)
+        .collect::<Vec<_>>();
+    assert_eq!(patches.len(), 2);
+    assert_eq!(patches[1].path, "/api/v1/namespaces/default/pods/backend");
+    assert_eq!(
+        patches[1].body.as_ref().unwrap()["metadata"]["labels"],
+        json!({ "tier": null })
+    );
+
+    let labels = api
+        .get::<Pod>("default", "backend")
+        .and_then(|pod| pod.metadata.labels)
+        .unwrap_or_default();
+    assert_eq!(labels.keys().collect::<Vec<_>>(), ["app"]);
+}
@@ -0,0 +1,71 @@
+use clap::Parser;
+use k8s_openapi::api::apps::v1::Deployment;
+use podium::{
+    client::Client,
+    k8s::fake::FakeApi,
+    wait::{run_with, Condition, WaitArgs},
+    Args,
+};
+use serde_json::json;
+use std::time::Duration;
+
+fn deployment(name: &str, progressing: &str) -> Deployment {
+    serde_json::from_value(json!({
+        "metadata": {
+            "name": name,
+            "namespace": "default",
+            "generation": 2,
+        },
+        "spec": {
+            "replicas": 2,
+            "selector": { "matchLabels": { "app": name } },
+            "template": {},
+        },
+        "status": {
+            "observedGeneration": 2,
+            "replicas": 2,
+            "updatedReplicas": 1,
+            "availableReplicas": 1,
+            "conditions": [{
+                "type": "Progressing",
+                "status": "False",
+                "reason": progressing,
+            }],
+        },
+    }))
+    .unwrap()
+}
+
+#[tokio::test]
+async fn exceeded_progress_deadline_fails_right_away() {
+    let api = FakeApi::new();
+    api.apply(&deployment("backend", "ProgressDeadlineExceeded"));
+
+    let client = Client::with_client(Args::parse_from(["podium"]), api.client());
+    let wait = WaitArgs {
+        conditions: vec![Condition::Rollout],
+        timeout: Duration::from_secs(300),
+        selector: None,
+    };
+
+    let result = tokio::time::timeout(Duration::from_secs(10), run_with(&client, &wait))
+        .await
+        .expect("failed before the timeout");
+    assert_eq!(result.unwrap_err().to_string(), "1 object(s) failed");
+}
+
+#[tokio::test]
+async fn pending_rollout_times_out() {
+    let api = FakeApi::new();
+    api.apply(&deployment("backend", "ReplicaSetUpdated"));
+
+    let client = Client::with_client(Args::parse_from(["podium"]), api.client());
+    let wait = WaitArgs {
+        conditions: vec![Condition::Rollout],
+        timeout: Duration::from_millis(200),
+        selector: None,
+    };
+
+    let result = run_with(&client, &wait).await;
+    assert_eq!(result.unwrap_err().to_string(), "Timed out after 200ms");
+}